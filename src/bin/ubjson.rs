@@ -0,0 +1,52 @@
+//! Command-line tools for UBJSON files.
+//!
+//! ```text
+//! ubjson diff <old.ubj> <new.ubj>
+//! ubjson query <expr> <file.ubj>
+//! ```
+
+use std::process::ExitCode;
+use ubjson_rs::{diff, value_from_slice, Query, UbjsonValue};
+
+const USAGE: &str = "usage: ubjson diff <old.ubj> <new.ubj>\n       ubjson query <expr> <file.ubj>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["diff", old, new] => run_diff(old, new),
+        ["query", expr, path] => run_query(expr, path),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn read_value(path: &str) -> Result<UbjsonValue, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    value_from_slice(&bytes).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Print one change per line. Like diff(1), exits with 1 when the files differ.
+fn run_diff(old: &str, new: &str) -> Result<ExitCode, String> {
+    let changes = diff(&read_value(old)?, &read_value(new)?);
+    for change in &changes {
+        println!("{}", change);
+    }
+    Ok(if changes.is_empty() { ExitCode::SUCCESS } else { ExitCode::from(1) })
+}
+
+/// Print each matching value on its own line. Like grep(1), exits with 1 when nothing matches.
+fn run_query(expr: &str, path: &str) -> Result<ExitCode, String> {
+    let query = Query::parse(expr).map_err(|e| e.to_string())?;
+    let value = read_value(path)?;
+    let matches = query.select(&value);
+    for value in &matches {
+        println!("{}", value);
+    }
+    Ok(if matches.is_empty() { ExitCode::from(1) } else { ExitCode::SUCCESS })
+}
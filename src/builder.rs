@@ -0,0 +1,140 @@
+//! Fluent construction of object values.
+
+use std::collections::HashMap;
+use crate::error::Result;
+use crate::patch::coerce;
+use crate::types::UbjsonType;
+use crate::value::UbjsonValue;
+
+/// Builder for [`UbjsonValue::Object`].
+///
+/// ```rust
+/// use ubjson_rs::{ObjectBuilder, UbjsonType, UbjsonValue};
+///
+/// let value = ObjectBuilder::new()
+///     .insert("name", "Falco")
+///     .insert("port", 2)
+///     .insert_typed_array("xs", UbjsonType::Float32, vec![1.0, 2.5])
+///     .build();
+/// assert_eq!(value.query("$.port").unwrap(), vec![&UbjsonValue::Int32(2)]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectBuilder {
+    pairs: HashMap<String, UbjsonValue>,
+}
+
+impl ObjectBuilder {
+    /// Create a builder for an empty object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry, replacing any earlier entry with the same key.
+    pub fn insert<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<UbjsonValue>,
+    {
+        self.pairs.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add a counted strongly-typed array of `elements`.
+    ///
+    /// Numbers are converted to `element_type` when that loses nothing. If an
+    /// element doesn't fit, a plain array is added instead.
+    pub fn insert_typed_array<K, I>(self, key: K, element_type: UbjsonType, elements: I) -> Self
+    where
+        K: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<UbjsonValue>,
+    {
+        let (elements, fits) = coerce_all(elements.into_iter().map(Into::into), element_type);
+        let array = if fits {
+            UbjsonValue::strongly_typed_array(element_type, elements)
+        } else {
+            UbjsonValue::Array(elements)
+        };
+        self.insert(key, array)
+    }
+
+    /// Add a counted strongly-typed array of `elements`, failing like
+    /// [`UbjsonValue::try_strongly_typed_array`] if an element doesn't fit
+    /// `element_type` after conversion.
+    pub fn try_insert_typed_array<K, I>(self, key: K, element_type: UbjsonType, elements: I) -> Result<Self>
+    where
+        K: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<UbjsonValue>,
+    {
+        let (elements, _) = coerce_all(elements.into_iter().map(Into::into), element_type);
+        let array = UbjsonValue::try_strongly_typed_array(element_type, elements)?;
+        Ok(self.insert(key, array))
+    }
+
+    /// Add a counted strongly-typed object of `pairs`.
+    ///
+    /// Numbers are converted to `value_type` when that loses nothing. If a
+    /// value doesn't fit, a plain object is added instead.
+    pub fn insert_typed_object<K, I, PK, PV>(self, key: K, value_type: UbjsonType, pairs: I) -> Self
+    where
+        K: Into<String>,
+        I: IntoIterator<Item = (PK, PV)>,
+        PK: Into<String>,
+        PV: Into<UbjsonValue>,
+    {
+        let (keys, values): (Vec<String>, Vec<UbjsonValue>) =
+            pairs.into_iter().map(|(k, v)| (k.into(), v.into())).unzip();
+        let (values, fits) = coerce_all(values.into_iter(), value_type);
+        let pairs = keys.into_iter().zip(values).collect();
+        let object = if fits {
+            UbjsonValue::strongly_typed_object(value_type, pairs)
+        } else {
+            UbjsonValue::Object(pairs)
+        };
+        self.insert(key, object)
+    }
+
+    /// Add a counted strongly-typed object of `pairs`, failing like
+    /// [`UbjsonValue::try_strongly_typed_object`] if a value doesn't fit
+    /// `value_type` after conversion.
+    pub fn try_insert_typed_object<K, I, PK, PV>(self, key: K, value_type: UbjsonType, pairs: I) -> Result<Self>
+    where
+        K: Into<String>,
+        I: IntoIterator<Item = (PK, PV)>,
+        PK: Into<String>,
+        PV: Into<UbjsonValue>,
+    {
+        let (keys, values): (Vec<String>, Vec<UbjsonValue>) =
+            pairs.into_iter().map(|(k, v)| (k.into(), v.into())).unzip();
+        let (values, _) = coerce_all(values.into_iter(), value_type);
+        let object = UbjsonValue::try_strongly_typed_object(value_type, keys.into_iter().zip(values).collect())?;
+        Ok(self.insert(key, object))
+    }
+
+    /// Finish the object.
+    pub fn build(self) -> UbjsonValue {
+        UbjsonValue::Object(self.pairs)
+    }
+}
+
+impl From<ObjectBuilder> for UbjsonValue {
+    fn from(builder: ObjectBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Convert each value to `element_type` where possible, and report whether
+/// they all fit a typed container of that type.
+fn coerce_all(values: impl Iterator<Item = UbjsonValue>, element_type: UbjsonType) -> (Vec<UbjsonValue>, bool) {
+    let mut fits = element_type.is_primitive();
+    let values = values
+        .map(|value| {
+            coerce(value, element_type).unwrap_or_else(|value| {
+                fits = false;
+                value
+            })
+        })
+        .collect();
+    (values, fits)
+}
@@ -0,0 +1,182 @@
+//! Structural differences between two values.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use crate::patch::{Patch, PatchOperation};
+use crate::path::{format_path, format_pointer, PathSegment};
+use crate::value::UbjsonValue;
+
+/// One difference found by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// An object entry or array element only present in the new value.
+    Added { path: Vec<PathSegment>, value: UbjsonValue },
+    /// An object entry or array element only present in the old value.
+    Removed { path: Vec<PathSegment>, value: UbjsonValue },
+    /// A value that differs logically.
+    Changed { path: Vec<PathSegment>, old: UbjsonValue, new: UbjsonValue },
+    /// A value that is semantically the same but encoded differently, such as
+    /// `Int16(5)` and `Int32(5)`, or a typed and a plain array. For containers
+    /// this only describes the container itself; differences inside it are
+    /// reported separately.
+    EncodingChanged { path: Vec<PathSegment>, old: UbjsonValue, new: UbjsonValue },
+}
+
+impl Change {
+    /// The location of the change, relative to the compared values.
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. }
+            | Change::EncodingChanged { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { path, value } => write!(f, "+ {}: {}", format_path(path), value),
+            Change::Removed { path, value } => write!(f, "- {}: {}", format_path(path), value),
+            Change::Changed { path, old, new } => write!(f, "~ {}: {} -> {}", format_path(path), old, new),
+            Change::EncodingChanged { path, old, new } => {
+                write!(f, "= {}: {} -> {}", format_path(path), encoding(old), encoding(new))
+            }
+        }
+    }
+}
+
+/// Describe how a value is encoded, for [`Change::EncodingChanged`].
+fn encoding(value: &UbjsonValue) -> String {
+    match value {
+        UbjsonValue::StronglyTypedArray { element_type, count, .. }
+        | UbjsonValue::StronglyTypedObject { value_type: element_type, count, .. } => format!(
+            "{} of {}{}",
+            value.type_name(),
+            element_type,
+            if count.is_some() { "" } else { " (uncounted)" }
+        ),
+        _ => value.type_name().to_string(),
+    }
+}
+
+/// List the differences between `old` and `new`.
+///
+/// Changes are listed depth first: object entries in key order and array
+/// elements by index. Elements removed from the end of an array are listed
+/// from the highest index down, so the list can be applied in order as a patch.
+pub fn diff(old: &UbjsonValue, new: &UbjsonValue) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut stack = vec![Task::Compare(Vec::new(), old, new)];
+    while let Some(task) = stack.pop() {
+        let (path, old, new) = match task {
+            Task::Compare(path, old, new) => (path, old, new),
+            Task::Emit(change) => {
+                changes.push(change);
+                continue;
+            }
+        };
+        if old == new {
+            continue;
+        }
+
+        // Children are queued before the additions and removals that follow
+        // them, in reverse because the stack pops the last task first
+        let mut tasks = Vec::new();
+        if let (Some(a), Some(b)) = (old.as_array(), new.as_array()) {
+            for (index, (x, y)) in a.iter().zip(b).enumerate() {
+                tasks.push(Task::Compare(child(&path, PathSegment::Index(index)), x, y));
+            }
+            for (index, value) in b.iter().enumerate().skip(a.len()) {
+                let path = child(&path, PathSegment::Index(index));
+                tasks.push(Task::Emit(Change::Added { path, value: value.clone() }));
+            }
+            for (index, value) in a.iter().enumerate().skip(b.len()).rev() {
+                let path = child(&path, PathSegment::Index(index));
+                tasks.push(Task::Emit(Change::Removed { path, value: value.clone() }));
+            }
+        } else if let (Some(a), Some(b)) = (old.as_object(), new.as_object()) {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let path = child(&path, PathSegment::Key(key.clone()));
+                tasks.push(match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => Task::Compare(path, x, y),
+                    (Some(x), None) => Task::Emit(Change::Removed { path, value: x.clone() }),
+                    (None, Some(y)) => Task::Emit(Change::Added { path, value: y.clone() }),
+                    (None, None) => unreachable!("key comes from one of the objects"),
+                });
+            }
+        } else if old.semantic_eq(new) {
+            // NaN never equals itself, so only report a real encoding difference
+            if !same_encoding(old, new) {
+                changes.push(Change::EncodingChanged { path, old: old.clone(), new: new.clone() });
+            }
+            continue;
+        } else {
+            changes.push(Change::Changed { path, old: old.clone(), new: new.clone() });
+            continue;
+        }
+
+        if !same_encoding(old, new) {
+            changes.push(Change::EncodingChanged { path, old: old.clone(), new: new.clone() });
+        }
+        stack.extend(tasks.into_iter().rev());
+    }
+    changes
+}
+
+enum Task<'a> {
+    Compare(Vec<PathSegment>, &'a UbjsonValue, &'a UbjsonValue),
+    Emit(Change),
+}
+
+fn child(path: &[PathSegment], segment: PathSegment) -> Vec<PathSegment> {
+    let mut path = path.to_vec();
+    path.push(segment);
+    path
+}
+
+/// Whether two values use the same variant, and for typed containers the
+/// same element type and counting.
+fn same_encoding(a: &UbjsonValue, b: &UbjsonValue) -> bool {
+    match (a, b) {
+        (
+            UbjsonValue::StronglyTypedArray { element_type: ta, count: ca, .. },
+            UbjsonValue::StronglyTypedArray { element_type: tb, count: cb, .. },
+        )
+        | (
+            UbjsonValue::StronglyTypedObject { value_type: ta, count: ca, .. },
+            UbjsonValue::StronglyTypedObject { value_type: tb, count: cb, .. },
+        ) => ta == tb && ca.is_some() == cb.is_some(),
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
+impl From<&[Change]> for Patch {
+    /// Build a patch that turns the old value into the new one.
+    ///
+    /// Encoding changes become `replace` operations, so applying the patch
+    /// reproduces the new value exactly. Changes inside a container that is
+    /// replaced as a whole are left out.
+    fn from(changes: &[Change]) -> Self {
+        let mut replaced: Vec<&[PathSegment]> = Vec::new();
+        let mut operations = Vec::new();
+        for change in changes {
+            let path = change.path();
+            if replaced.iter().any(|prefix| path.starts_with(prefix)) {
+                continue;
+            }
+            let pointer = format_pointer(path);
+            operations.push(match change {
+                Change::Added { value, .. } => PatchOperation::Add { path: pointer, value: value.clone() },
+                Change::Removed { .. } => PatchOperation::Remove { path: pointer },
+                Change::Changed { new, .. } | Change::EncodingChanged { new, .. } => {
+                    replaced.push(path);
+                    PatchOperation::Replace { path: pointer, value: new.clone() }
+                }
+            });
+        }
+        Patch(operations)
+    }
+}
@@ -33,28 +33,28 @@ pub fn read_length<R: Read>(reader: &mut R) -> Result<usize> {
         UbjsonType::Int8 => {
             let value = read_int8(reader)?;
             if value < 0 {
-                return Err(UbjsonError::invalid_format("Negative length not allowed"));
+                return Err(UbjsonError::NegativeLength(value.into()));
             }
             Ok(value as usize)
         }
         UbjsonType::Int16 => {
             let value = read_int16(reader)?;
             if value < 0 {
-                return Err(UbjsonError::invalid_format("Negative length not allowed"));
+                return Err(UbjsonError::NegativeLength(value.into()));
             }
             Ok(value as usize)
         }
         UbjsonType::Int32 => {
             let value = read_int32(reader)?;
             if value < 0 {
-                return Err(UbjsonError::invalid_format("Negative length not allowed"));
+                return Err(UbjsonError::NegativeLength(value.into()));
             }
             Ok(value as usize)
         }
         UbjsonType::Int64 => {
             let value = read_int64(reader)?;
             if value < 0 {
-                return Err(UbjsonError::invalid_format("Negative length not allowed"));
+                return Err(UbjsonError::NegativeLength(value));
             }
             if value > usize::MAX as i64 {
                 return Err(UbjsonError::invalid_format("Length too large for platform"));
@@ -180,11 +180,17 @@ pub fn write_float64<W: Write>(writer: &mut W, value: f64) -> Result<()> {
 /// Read a UTF-8 string from the reader. The string is prefixed with its length.
 pub fn read_string<R: Read>(reader: &mut R) -> Result<String> {
     let length = read_length(reader)?;
-    let mut buffer = vec![0u8; length];
-    reader.read_exact(&mut buffer)?;
-    
-    let string = std::str::from_utf8(&buffer)?;
-    Ok(string.to_string())
+
+    // The declared length is untrusted, so let the buffer grow with the data
+    // that actually arrives instead of allocating it all up front
+    let mut buffer = Vec::new();
+    let read = reader.take(length as u64).read_to_end(&mut buffer)?;
+    if read != length {
+        return Err(UbjsonError::UnexpectedEof);
+    }
+
+    let string = String::from_utf8(buffer).map_err(|e| e.utf8_error())?;
+    Ok(string)
 }
 
 /// Write a UTF-8 string to the writer, prefixed with its length.
@@ -213,12 +219,10 @@ pub fn read_char<R: Read>(reader: &mut R) -> Result<char> {
     };
     
     if char_len > 1 {
-        let mut full_buffer = vec![first_byte];
-        let mut remaining = vec![0u8; char_len - 1];
-        reader.read_exact(&mut remaining)?;
-        full_buffer.extend_from_slice(&remaining);
+        let mut full_buffer = [first_byte, 0, 0, 0];
+        reader.read_exact(&mut full_buffer[1..char_len])?;
         
-        let string = std::str::from_utf8(&full_buffer)?;
+        let string = std::str::from_utf8(&full_buffer[..char_len])?;
         let chars: Vec<char> = string.chars().collect();
         if chars.len() != 1 {
             return Err(UbjsonError::InvalidChar(format!(
@@ -1,13 +1,33 @@
 //! Error types for UBJSON operations.
 
 use std::fmt;
+use std::io;
+use crate::types::UbjsonType;
+use crate::value::UbjsonValue;
+
+/// Broad category of a [`UbjsonError`], for callers that match on failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The input ended before a complete value was read.
+    Eof,
+    /// The input is not well-formed UBJSON.
+    Syntax,
+    /// A configured depth or size limit was exceeded.
+    Limit,
+    /// The input is well-formed but its content is invalid or has the wrong type.
+    Data,
+    /// The underlying reader or writer failed.
+    Io,
+    /// A custom error raised by a `Serialize` or `Deserialize` implementation.
+    Custom,
+}
 
 /// Errors that can occur during UBJSON serialization and deserialization.
 #[derive(Debug, thiserror::Error)]
 pub enum UbjsonError {
     /// I/O error occurred during reading or writing.
     #[error("I/O error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(#[source] std::io::Error),
 
     /// Invalid UBJSON format encountered.
     #[error("Invalid UBJSON format: {0}")]
@@ -29,6 +49,22 @@ pub enum UbjsonError {
     #[error("Nesting depth limit exceeded: {0}")]
     DepthLimitExceeded(usize),
 
+    /// Total input size budget exceeded.
+    #[error("Total input size limit exceeded: {0} bytes")]
+    TotalBytesLimitExceeded(usize),
+
+    /// Total number of decoded values exceeded.
+    #[error("Total value count limit exceeded: {0}")]
+    ValueCountLimitExceeded(usize),
+
+    /// Total size of all strings exceeded.
+    #[error("Total string size limit exceeded: {0} bytes")]
+    StringBytesLimitExceeded(usize),
+
+    /// A single string or object key is too long.
+    #[error("String length limit exceeded: {0} bytes")]
+    StringLengthLimitExceeded(usize),
+
     /// Unsupported type encountered.
     #[error("Unsupported type: {0}")]
     UnsupportedType(String),
@@ -52,6 +88,46 @@ pub enum UbjsonError {
     /// Invalid character value.
     #[error("Invalid character value: {0}")]
     InvalidChar(String),
+
+    /// A length or count was encoded as a negative integer.
+    #[error("Negative length not allowed: {0}")]
+    NegativeLength(i64),
+
+    /// A container end marker appeared where a value was expected.
+    #[error("Unexpected container end marker: {0}")]
+    UnexpectedContainerEnd(UbjsonType),
+
+    /// A value in a strongly-typed container does not have the container's type.
+    #[error("Value type {found} does not match expected type {expected} of strongly-typed container")]
+    TypedContainerMismatch { expected: UbjsonType, found: UbjsonType },
+
+    /// A value has a different type than the one being deserialized.
+    #[error("Invalid type: expected {expected}, found {found}")]
+    InvalidType { expected: &'static str, found: &'static str },
+
+    /// Valid input for this crate that strict mode rejects as not conforming to the UBJSON specification.
+    #[error("Non-conformant UBJSON: {0}")]
+    NonConformant(String),
+
+    /// Input remained after the end of the value.
+    #[error("Trailing data after value at offset {offset}")]
+    TrailingData { offset: usize },
+
+    /// A patch document or JSON Pointer is malformed.
+    #[error("Invalid patch: {0}")]
+    InvalidPatch(String),
+
+    /// A patch operation could not be applied to the value.
+    #[error("Patch operation {index} failed: {reason}")]
+    PatchFailed { index: usize, reason: String },
+
+    /// A query expression is malformed.
+    #[error("Invalid query at offset {position}: {reason}")]
+    InvalidQuery { position: usize, reason: String },
+
+    /// The output buffer cannot hold the encoded value.
+    #[error("Output buffer too small: {needed} bytes needed")]
+    BufferTooSmall { needed: usize },
 }
 
 impl UbjsonError {
@@ -69,6 +145,74 @@ impl UbjsonError {
     pub fn serde<T: fmt::Display>(msg: T) -> Self {
         UbjsonError::Serde(msg.to_string())
     }
+
+    /// Create an InvalidType error for a value that isn't the `expected` type.
+    pub(crate) fn invalid_type(expected: &'static str, value: &UbjsonValue) -> Self {
+        UbjsonError::InvalidType { expected, found: value.type_name() }
+    }
+
+    /// Get the broad category of this error.
+    pub fn classify(&self) -> ErrorKind {
+        match self {
+            UbjsonError::UnexpectedEof => ErrorKind::Eof,
+            UbjsonError::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof => ErrorKind::Eof,
+            UbjsonError::Io(_) => ErrorKind::Io,
+            UbjsonError::InvalidFormat(_)
+            | UbjsonError::InvalidTypeMarker(_)
+            | UbjsonError::NegativeLength(_)
+            | UbjsonError::UnexpectedContainerEnd(_)
+            | UbjsonError::TrailingData { .. }
+            | UbjsonError::NonConformant(_) => ErrorKind::Syntax,
+            UbjsonError::SizeLimitExceeded(_)
+            | UbjsonError::DepthLimitExceeded(_)
+            | UbjsonError::TotalBytesLimitExceeded(_)
+            | UbjsonError::ValueCountLimitExceeded(_)
+            | UbjsonError::StringBytesLimitExceeded(_)
+            | UbjsonError::StringLengthLimitExceeded(_)
+            | UbjsonError::BufferTooSmall { .. } => ErrorKind::Limit,
+            UbjsonError::InvalidUtf8(_)
+            | UbjsonError::UnsupportedType(_)
+            | UbjsonError::LengthMismatch { .. }
+            | UbjsonError::InvalidHighPrecision(_)
+            | UbjsonError::InvalidChar(_)
+            | UbjsonError::TypedContainerMismatch { .. }
+            | UbjsonError::InvalidType { .. }
+            | UbjsonError::InvalidPatch(_)
+            | UbjsonError::PatchFailed { .. }
+            | UbjsonError::InvalidQuery { .. } => ErrorKind::Data,
+            UbjsonError::Serde(_) => ErrorKind::Custom,
+        }
+    }
+
+    /// Check whether this error was caused by the input ending too early.
+    pub fn is_eof(&self) -> bool {
+        self.classify() == ErrorKind::Eof
+    }
+}
+
+impl From<io::Error> for UbjsonError {
+    fn from(error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::UnexpectedEof {
+            UbjsonError::UnexpectedEof
+        } else {
+            UbjsonError::Io(error)
+        }
+    }
+}
+
+impl From<UbjsonError> for io::Error {
+    fn from(error: UbjsonError) -> Self {
+        let kind = match error.classify() {
+            ErrorKind::Io => match error {
+                UbjsonError::Io(e) => return e,
+                _ => io::ErrorKind::Other,
+            },
+            ErrorKind::Eof => io::ErrorKind::UnexpectedEof,
+            ErrorKind::Syntax | ErrorKind::Limit | ErrorKind::Data => io::ErrorKind::InvalidData,
+            ErrorKind::Custom => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, error)
+    }
 }
 
 /// Result type alias for UBJSON operations.
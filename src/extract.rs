@@ -0,0 +1,333 @@
+//! Streaming extraction of the values matching a [`Query`].
+//!
+//! The query is run against the input as it is read. Each value is paired
+//! with the query segments that have matched on the way to it, and values
+//! that no segment can still reach are skipped without being decoded.
+
+use crate::deserializer::UbjsonDeserializer;
+use crate::error::{Result, UbjsonError};
+use crate::path::PathSegment;
+use crate::query::{resolve_index, select_segments, slice_contains, Query, Segment, Selector};
+use crate::read::Read;
+use crate::types::optimization::TYPE_MARKER;
+use crate::types::UbjsonType;
+use crate::value::UbjsonValue;
+
+impl<'de, R: Read<'de>> UbjsonDeserializer<R> {
+    /// Read one value, calling `f` with each value inside it whose path matches `query`.
+    ///
+    /// Only matching values are decoded. Everything else is skipped, in one
+    /// step where a length or count header allows it, so memory use is
+    /// proportional to the matched values rather than to the document. The
+    /// size limit only applies to decoded containers.
+    ///
+    /// Some selectors need more than the matched values. A filter decodes
+    /// each element or member it tests, and a negative index or slice
+    /// decodes an array that doesn't declare a count. Filters that refer to
+    /// the root value with `$` are not supported.
+    pub fn extract<F>(&mut self, query: &Query, f: F) -> Result<()>
+    where
+        F: FnMut(&[PathSegment], UbjsonValue),
+    {
+        if query.refers_to_root() {
+            return Err(UbjsonError::unsupported_type(
+                "filters that refer to the root value cannot be streamed",
+            ));
+        }
+        let mut extractor = Extractor { segments: &query.segments, path: Vec::new(), f };
+        let type_marker = self.read_type_marker()?;
+        extractor.value(self, Element::Marked(type_marker), &[0])
+    }
+}
+
+/// How the next value is encoded.
+#[derive(Debug, Clone, Copy)]
+enum Element {
+    /// A value whose type marker has been read.
+    Marked(UbjsonType),
+    /// A value in a strongly-typed container, which has no marker of its own.
+    Raw(UbjsonType),
+}
+
+/// The header of a strongly-typed container: element type and optional count.
+type Header = Option<(UbjsonType, Option<usize>)>;
+
+struct Extractor<'q, F> {
+    segments: &'q [Segment],
+    path: Vec<PathSegment>,
+    f: F,
+}
+
+impl<F> Extractor<'_, F>
+where
+    F: FnMut(&[PathSegment], UbjsonValue),
+{
+    /// Handle the value at `self.path`.
+    ///
+    /// Each of the `states` is a number of query segments that have matched
+    /// on the way to this value; it matches the whole query when all have.
+    fn value<'de, R: Read<'de>>(
+        &mut self,
+        de: &mut UbjsonDeserializer<R>,
+        element: Element,
+        states: &[usize],
+    ) -> Result<()> {
+        let matched = states.contains(&self.segments.len());
+        let type_marker = match element {
+            _ if states.is_empty() => return skip(de, element),
+            Element::Marked(UbjsonType::NoOp) => {
+                let type_marker = de.read_type_marker()?;
+                return self.value(de, Element::Marked(type_marker), states);
+            }
+            _ if matched => {
+                let value = decode(de, element)?;
+                self.report(value, states);
+                return Ok(());
+            }
+            // Raw values are never containers, so nothing inside them can match
+            Element::Raw(value_type) => return de.skip_raw_value(value_type),
+            Element::Marked(type_marker) => type_marker,
+        };
+
+        let object = match type_marker {
+            UbjsonType::ArrayStart => false,
+            UbjsonType::ObjectStart => true,
+            _ => return de.skip_value_with_type(type_marker),
+        };
+        if !self.reaches_children(states, object) {
+            return de.skip_value_with_type(type_marker);
+        }
+        de.enter_container()?;
+        let result = if object { self.object(de, states) } else { self.array(de, states) };
+        de.leave_container();
+        result
+    }
+
+    fn array<'de, R: Read<'de>>(&mut self, de: &mut UbjsonDeserializer<R>, states: &[usize]) -> Result<()> {
+        let header = read_header(de, "arrays")?;
+        let len = header.and_then(|(_, count)| count);
+        if len.is_none() && self.needs_length(states) {
+            let value = read_array(de, header)?;
+            self.report(value, states);
+            return Ok(());
+        }
+
+        let mut index = 0;
+        while let Some(element) = next_element(de, header, index)? {
+            self.path.push(PathSegment::Index(index));
+            let result = self.child(de, element, states, len);
+            self.path.pop();
+            result?;
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn object<'de, R: Read<'de>>(&mut self, de: &mut UbjsonDeserializer<R>, states: &[usize]) -> Result<()> {
+        let header = read_header(de, "objects")?;
+        let mut index = 0;
+        loop {
+            let (key, element) = match header {
+                Some((_, Some(count))) if index == count => return Ok(()),
+                Some((_, None)) if de.peek_byte()? == b'}' => {
+                    de.next_byte()?;
+                    return Ok(());
+                }
+                Some((value_type, _)) => (de.read_string()?, Element::Raw(value_type)),
+                // Members are reported as they are read rather than stored,
+                // so the size limit doesn't apply
+                None => match de.next_object_key(0)? {
+                    Some((_, key)) => (key, Element::Marked(de.read_type_marker()?)),
+                    None => return Ok(()),
+                },
+            };
+            self.path.push(PathSegment::Key(key));
+            let result = self.child(de, element, states, None);
+            self.path.pop();
+            result?;
+            index += 1;
+        }
+    }
+
+    /// Handle a child of a container at `self.path`, where the container
+    /// has `states` and holds `len` elements if known.
+    fn child<'de, R: Read<'de>>(
+        &mut self,
+        de: &mut UbjsonDeserializer<R>,
+        element: Element,
+        states: &[usize],
+        len: Option<usize>,
+    ) -> Result<()> {
+        if !self.has_filter(states) {
+            let states = self.child_states(states, len, None);
+            return self.value(de, element, &states);
+        }
+
+        // A filter has to see the child before deciding whether it matches
+        let value = decode(de, element)?;
+        let states = self.child_states(states, len, Some(&value));
+        self.report(value, &states);
+        Ok(())
+    }
+
+    /// The states of the child at `self.path`. Filters match only when the
+    /// child's `value` is given.
+    fn child_states(&self, states: &[usize], len: Option<usize>, value: Option<&UbjsonValue>) -> Vec<usize> {
+        let segment = self.path.last().expect("a child has a path segment");
+        let mut next = Vec::new();
+        for &state in states {
+            let selectors = match &self.segments[state] {
+                Segment::Child(selectors) => selectors,
+                Segment::Descendant(selectors) => {
+                    next.push(state);
+                    selectors
+                }
+            };
+            if selectors.iter().any(|selector| selects(selector, segment, len, value)) {
+                next.push(state + 1);
+            }
+        }
+        next.sort_unstable();
+        next.dedup();
+        next
+    }
+
+    /// Report a decoded value at `self.path`, along with everything inside it
+    /// that matches the rest of the query from each of its `states`.
+    fn report(&mut self, value: UbjsonValue, states: &[usize]) {
+        let mut found = Vec::new();
+        for &state in states.iter().filter(|&&state| state < self.segments.len()) {
+            for (path, inner) in select_segments(&self.segments[state..], &value) {
+                found.push((path, inner.clone()));
+            }
+        }
+        if states.contains(&self.segments.len()) {
+            (self.f)(&self.path, value);
+        }
+        let depth = self.path.len();
+        for (path, inner) in found {
+            self.path.extend(path);
+            (self.f)(&self.path, inner);
+            self.path.truncate(depth);
+        }
+    }
+
+    /// Whether any of the `states` can select a child of an array or object.
+    fn reaches_children(&self, states: &[usize], object: bool) -> bool {
+        states.iter().any(|&state| match &self.segments[state] {
+            Segment::Descendant(_) => true,
+            Segment::Child(selectors) => selectors.iter().any(|selector| match selector {
+                Selector::Name(_) => object,
+                Selector::Index(_) | Selector::Slice { .. } => !object,
+                Selector::Wildcard | Selector::Filter(_) => true,
+            }),
+        })
+    }
+
+    /// Whether any of the `states` selects array elements counting from the end.
+    fn needs_length(&self, states: &[usize]) -> bool {
+        self.selectors(states).any(|selector| match *selector {
+            Selector::Index(index) => index < 0,
+            Selector::Slice { start, end, step } => {
+                step < 0 || start.is_some_and(|start| start < 0) || end.is_some_and(|end| end < 0)
+            }
+            _ => false,
+        })
+    }
+
+    fn has_filter(&self, states: &[usize]) -> bool {
+        self.selectors(states).any(|selector| matches!(selector, Selector::Filter(_)))
+    }
+
+    fn selectors<'a>(&'a self, states: &'a [usize]) -> impl Iterator<Item = &'a Selector> {
+        states.iter().flat_map(|&state| self.segments[state].selectors())
+    }
+}
+
+/// Whether `selector` picks the child at `segment` of a container holding
+/// `len` elements if known. Filters only match a given child `value`.
+fn selects(selector: &Selector, segment: &PathSegment, len: Option<usize>, value: Option<&UbjsonValue>) -> bool {
+    match (selector, segment) {
+        (Selector::Wildcard, _) => true,
+        (Selector::Name(name), PathSegment::Key(key)) => name == key,
+        (Selector::Index(index), PathSegment::Index(position)) => match len {
+            Some(len) => resolve_index(*index, len) == Some(*position),
+            None => usize::try_from(*index) == Ok(*position),
+        },
+        (Selector::Slice { start, end, step }, PathSegment::Index(position)) => {
+            slice_contains(*start, *end, *step, len, *position)
+        }
+        (Selector::Filter(filter), _) => value.is_some_and(|value| filter.matches(value, value)),
+        _ => false,
+    }
+}
+
+fn decode<'de, R: Read<'de>>(de: &mut UbjsonDeserializer<R>, element: Element) -> Result<UbjsonValue> {
+    match element {
+        Element::Marked(type_marker) => de.deserialize_value_with_type(type_marker),
+        Element::Raw(value_type) => de.deserialize_raw_value(value_type),
+    }
+}
+
+fn skip<'de, R: Read<'de>>(de: &mut UbjsonDeserializer<R>, element: Element) -> Result<()> {
+    match element {
+        Element::Marked(type_marker) => de.skip_value_with_type(type_marker),
+        Element::Raw(value_type) => de.skip_raw_value(value_type),
+    }
+}
+
+/// Read the strongly-typed container header after a start marker, if there is one.
+fn read_header<'de, R: Read<'de>>(de: &mut UbjsonDeserializer<R>, container: &str) -> Result<Header> {
+    if de.peek_byte()? != TYPE_MARKER {
+        return Ok(None);
+    }
+    de.next_byte()?;
+    de.read_container_header(container).map(Some)
+}
+
+/// Read up to the next element of an array, or past its end.
+fn next_element<'de, R: Read<'de>>(
+    de: &mut UbjsonDeserializer<R>,
+    header: Header,
+    index: usize,
+) -> Result<Option<Element>> {
+    match header {
+        Some((element_type, Some(count))) => Ok((index < count).then_some(Element::Raw(element_type))),
+        Some((element_type, None)) => {
+            if de.peek_byte()? == b']' {
+                de.next_byte()?;
+                Ok(None)
+            } else {
+                Ok(Some(Element::Raw(element_type)))
+            }
+        }
+        None => loop {
+            match de.peek_byte()? {
+                b']' => {
+                    de.next_byte()?;
+                    return Ok(None);
+                }
+                // No-op markers are padding between elements
+                b'N' => {
+                    de.next_byte()?;
+                }
+                _ => return Ok(Some(Element::Marked(de.read_type_marker()?))),
+            }
+        },
+    }
+}
+
+/// Decode the rest of an array without a count.
+fn read_array<'de, R: Read<'de>>(de: &mut UbjsonDeserializer<R>, header: Header) -> Result<UbjsonValue> {
+    let mut elements = Vec::new();
+    while let Some(element) = next_element(de, header, elements.len())? {
+        if elements.len() >= de.max_size() {
+            return Err(UbjsonError::SizeLimitExceeded(de.max_size()));
+        }
+        elements.push(decode(de, element)?);
+    }
+    Ok(match header {
+        Some((element_type, _)) => UbjsonValue::StronglyTypedArray { element_type, count: None, elements },
+        None => UbjsonValue::Array(elements),
+    })
+}
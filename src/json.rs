@@ -0,0 +1,178 @@
+//! Conversions between [`UbjsonValue`] and `serde_json::Value`.
+//!
+//! Enabled by the `serde_json` feature.
+//!
+//! # From JSON
+//!
+//! Every JSON value converts:
+//!
+//! - Numbers that fit in `i64` become `Int64`.
+//! - Larger `u64` numbers become `HighPrecision` with their decimal digits.
+//! - Other numbers become `Float64`, unless that would lose digits of the
+//!   original text, which only happens when serde_json's `arbitrary_precision`
+//!   feature is on. Those numbers become `HighPrecision` holding the exact text.
+//! - Strings, arrays and objects map to `String`, `Array` and `Object`.
+//!
+//! # To JSON
+//!
+//! The conversion is fallible:
+//!
+//! - Integers of any width become JSON integers.
+//! - `Float32` goes through its shortest decimal form, so `0.1f32` becomes `0.1`.
+//! - NaN and infinite floats are rejected with [`UbjsonError::UnsupportedType`]
+//!   because JSON cannot represent them.
+//! - `HighPrecision` is parsed as a JSON number. Text that is not a valid JSON
+//!   number, or that is out of range for serde_json, is rejected with
+//!   [`UbjsonError::InvalidHighPrecision`].
+//! - `Char` becomes a one-character string.
+//! - Strongly-typed arrays and objects become plain arrays and objects.
+//!
+//! Going through JSON therefore loses the distinction between integer widths,
+//! chars and strings, and typed and plain containers.
+
+use crate::{UbjsonError, UbjsonValue};
+use serde_json::{Map, Number, Value};
+use std::convert::Infallible;
+
+impl From<Value> for UbjsonValue {
+    fn from(value: Value) -> Self {
+        let Ok(value) = convert(value, from_json_node, UbjsonValue::Array, |pairs| {
+            UbjsonValue::Object(pairs.into_iter().collect())
+        });
+        value
+    }
+}
+
+fn from_json_node(value: Value) -> Result<Node<Value, UbjsonValue>, Infallible> {
+    Ok(Node::Leaf(match value {
+        Value::Null => UbjsonValue::Null,
+        Value::Bool(b) => UbjsonValue::Bool(b),
+        Value::Number(n) => from_number(n),
+        Value::String(s) => UbjsonValue::String(s),
+        Value::Array(elements) => return Ok(Node::Array(elements)),
+        Value::Object(pairs) => return Ok(Node::Object(pairs.into_iter().collect())),
+    }))
+}
+
+fn from_number(n: Number) -> UbjsonValue {
+    if let Some(i) = n.as_i64() {
+        return UbjsonValue::Int64(i);
+    }
+    if let Some(u) = n.as_u64() {
+        return UbjsonValue::HighPrecision(u.to_string());
+    }
+    // Only an arbitrary-precision number can differ from its f64 form
+    match n.as_f64() {
+        Some(f) if Number::from_f64(f).is_some_and(|m| m.to_string() == n.to_string()) => UbjsonValue::Float64(f),
+        _ => UbjsonValue::HighPrecision(n.to_string()),
+    }
+}
+
+impl TryFrom<UbjsonValue> for Value {
+    type Error = UbjsonError;
+
+    fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+        convert(value, to_json_node, Value::Array, |pairs| Value::Object(pairs.into_iter().collect::<Map<_, _>>()))
+    }
+}
+
+fn to_json_node(value: UbjsonValue) -> Result<Node<UbjsonValue, Value>, UbjsonError> {
+    Ok(Node::Leaf(match value {
+        UbjsonValue::Null => Value::Null,
+        UbjsonValue::Bool(b) => Value::Bool(b),
+        UbjsonValue::Int8(n) => Value::from(n),
+        UbjsonValue::UInt8(n) => Value::from(n),
+        UbjsonValue::Int16(n) => Value::from(n),
+        UbjsonValue::Int32(n) => Value::from(n),
+        UbjsonValue::Int64(n) => Value::from(n),
+        // The shortest decimal form of an f32 avoids digits it never had
+        UbjsonValue::Float32(f) => float_to_json(f.to_string().parse().unwrap_or(f as f64))?,
+        UbjsonValue::Float64(f) => float_to_json(f)?,
+        UbjsonValue::HighPrecision(s) => match s.parse::<Number>() {
+            Ok(n) => Value::Number(n),
+            Err(_) => return Err(UbjsonError::InvalidHighPrecision(s)),
+        },
+        UbjsonValue::Char(c) => Value::String(c.to_string()),
+        UbjsonValue::String(s) => Value::String(s),
+        UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+            return Ok(Node::Array(elements));
+        }
+        UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => {
+            return Ok(Node::Object(pairs.into_iter().collect()));
+        }
+    }))
+}
+
+fn float_to_json(f: f64) -> Result<Value, UbjsonError> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| UbjsonError::UnsupportedType(format!("non-finite float {} in JSON", f)))
+}
+
+/// One level of a tree being converted: a finished leaf, or the children of a container.
+enum Node<S, T> {
+    Leaf(T),
+    Array(Vec<S>),
+    Object(Vec<(String, S)>),
+}
+
+/// A container whose children are being converted, with the key of the
+/// child in progress for objects.
+enum Frame<S, T> {
+    Array(Vec<T>, std::vec::IntoIter<S>),
+    Object(Vec<(String, T)>, std::vec::IntoIter<(String, S)>, String),
+}
+
+/// Convert a tree one level at a time with an explicit stack, so deeply
+/// nested values can't overflow the call stack.
+fn convert<S, T, E>(
+    root: S,
+    mut split: impl FnMut(S) -> Result<Node<S, T>, E>,
+    array: impl Fn(Vec<T>) -> T,
+    object: impl Fn(Vec<(String, T)>) -> T,
+) -> Result<T, E> {
+    let mut stack = Vec::new();
+    let mut next = root;
+    loop {
+        let mut done = match split(next)? {
+            Node::Leaf(value) => Some(value),
+            Node::Array(elements) => {
+                stack.push(Frame::Array(Vec::with_capacity(elements.len()), elements.into_iter()));
+                None
+            }
+            Node::Object(pairs) => {
+                stack.push(Frame::Object(Vec::with_capacity(pairs.len()), pairs.into_iter(), String::new()));
+                None
+            }
+        };
+        // Hand finished values to their containers until one has a child left
+        next = loop {
+            let child = match stack.last_mut() {
+                None => return Ok(done.expect("the root is finished once the stack is empty")),
+                Some(Frame::Array(converted, rest)) => {
+                    converted.extend(done.take());
+                    rest.next()
+                }
+                Some(Frame::Object(converted, rest, key)) => {
+                    if let Some(value) = done.take() {
+                        converted.push((std::mem::take(key), value));
+                    }
+                    rest.next().map(|(next_key, value)| {
+                        *key = next_key;
+                        value
+                    })
+                }
+            };
+            match child {
+                Some(child) => break child,
+                None => {
+                    done = match stack.pop() {
+                        Some(Frame::Array(converted, _)) => Some(array(converted)),
+                        Some(Frame::Object(converted, _, _)) => Some(object(converted)),
+                        None => None,
+                    };
+                }
+            }
+        };
+    }
+}
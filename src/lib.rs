@@ -9,6 +9,7 @@
 //! - Full UBJSON specification support
 //! - Container optimization for homogeneous collections
 //! - Serde integration for automatic derive support
+//! - Conversions to and from `serde_json::Value` with the `serde_json` feature
 //! - Zero-copy deserialization where possible
 //! - Comprehensive error handling and validation
 //! - Performance optimizations for large datasets
@@ -84,20 +85,40 @@
 //! assert_eq!(data, deserialized);
 //! ```
 
+pub mod builder;
 pub mod deserializer;
+pub mod diff;
 pub mod encoding;
 pub mod error;
+mod extract;
+#[cfg(feature = "serde_json")]
+mod json;
+pub mod patch;
+pub mod path;
+pub mod query;
+pub mod read;
+pub mod semantic;
 pub mod serializer;
 #[cfg(feature = "serde")]
 pub mod serde_impl;
+pub mod transform;
 pub mod types;
+pub mod validate;
 pub mod value;
 
 // Re-export main types for convenience
-pub use deserializer::UbjsonDeserializer;
-pub use error::{UbjsonError, Result};
+pub use builder::ObjectBuilder;
+pub use deserializer::{DuplicateKeys, PartialDocument, RecoveredError, UbjsonDeserializer, Warning, WarningKind};
+pub use diff::{diff, Change};
+pub use error::{ErrorKind, UbjsonError, Result};
+pub use patch::{Patch, PatchOperation};
+pub use path::{format_path, format_pointer, PathSegment};
+pub use query::Query;
+pub use semantic::SemanticValue;
 pub use serializer::UbjsonSerializer;
+pub use transform::NormalizeOptions;
 pub use types::UbjsonType;
+pub use validate::{Violation, ViolationKind};
 pub use value::UbjsonValue;
 
 // High-level convenience functions for serde integration
@@ -121,14 +142,72 @@ where
     value.serialize(serializer)
 }
 
+/// Serialize a value into `buffer`, returning the number of bytes written.
+///
+/// Fails with [`UbjsonError::BufferTooSmall`] if the encoding doesn't fit.
 #[cfg(feature = "serde")]
-pub fn from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+pub fn to_slice<T>(buffer: &mut [u8], value: &T) -> Result<usize>
+where
+    T: serde::Serialize,
+{
+    SerializerBuilder::new().to_slice(buffer, value)
+}
+
+/// Convert a serializable value into a UbjsonValue tree without encoding it.
+///
+/// The tree is the same one that decoding the output of [`to_vec`] would give.
+#[cfg(feature = "serde")]
+pub fn to_value<T>(value: &T) -> Result<UbjsonValue>
+where
+    T: ?Sized + serde::Serialize,
+{
+    value.serialize(serde_impl::ValueSerializer)
+}
+
+/// Deserialize a value from a UbjsonValue tree without encoding it.
+#[cfg(feature = "serde")]
+pub fn from_value<T>(value: UbjsonValue) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
 {
-    from_reader(slice)
+    T::deserialize(value)
+}
+
+/// Deserialize a value that borrows strings and keys from a UbjsonValue tree.
+#[cfg(feature = "serde")]
+pub fn from_value_ref<'de, T>(value: &'de UbjsonValue) -> Result<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    T::deserialize(value)
 }
 
+#[cfg(feature = "serde")]
+pub fn from_slice<T>(slice: &[u8]) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    DeserializerBuilder::new().from_slice(slice)
+}
+
+/// Deserialize the first value in a byte slice, returning it with the number of bytes it used.
+///
+/// Bytes after the value are left unread, so a buffer holding several values
+/// can be walked by slicing off the consumed prefix each time.
+#[cfg(feature = "serde")]
+pub fn from_slice_partial<T>(slice: &[u8]) -> Result<(T, usize)>
+where
+    T: serde::de::DeserializeOwned,
+{
+    DeserializerBuilder::new().from_slice_partial(slice)
+}
+
+/// Deserialize a value from a reader.
+///
+/// The reader is buffered internally, so it may be advanced past the end of
+/// the value. To read several values from one stream, use a single
+/// [`UbjsonDeserializer`], or recover the unread bytes with
+/// [`UbjsonDeserializer::into_inner`].
 #[cfg(feature = "serde")]
 pub fn from_reader<R, T>(reader: R) -> Result<T>
 where
@@ -154,10 +233,33 @@ where
     serializer.serialize_value(value)
 }
 
+/// Serialize a UbjsonValue into `buffer`, returning the number of bytes written.
+///
+/// Fails with [`UbjsonError::BufferTooSmall`] if the encoding doesn't fit.
+pub fn value_to_slice(buffer: &mut [u8], value: &UbjsonValue) -> Result<usize> {
+    SerializerBuilder::new().value_to_slice(buffer, value)
+}
+
+/// Compute the number of bytes [`value_to_vec`] would produce, without writing them.
+pub fn encoded_len(value: &UbjsonValue) -> Result<usize> {
+    SerializerBuilder::new().value_encoded_len(value)
+}
+
 pub fn value_from_slice(slice: &[u8]) -> Result<UbjsonValue> {
-    value_from_reader(slice)
+    DeserializerBuilder::new().value_from_slice(slice)
 }
 
+/// Deserialize the first UbjsonValue in a byte slice, returning it with the number of bytes it used.
+pub fn value_from_slice_partial(slice: &[u8]) -> Result<(UbjsonValue, usize)> {
+    DeserializerBuilder::new().value_from_slice_partial(slice)
+}
+
+/// Deserialize a UbjsonValue from a reader.
+///
+/// The reader is buffered internally, so it may be advanced past the end of
+/// the value. To read several values from one stream, use a single
+/// [`UbjsonDeserializer`], or recover the unread bytes with
+/// [`UbjsonDeserializer::into_inner`].
 pub fn value_from_reader<R>(reader: R) -> Result<UbjsonValue>
 where
     R: std::io::Read,
@@ -171,6 +273,7 @@ where
 pub struct SerializerBuilder {
     optimize_containers: bool,
     max_depth: usize,
+    strict: bool,
 }
 
 impl Default for SerializerBuilder {
@@ -178,6 +281,7 @@ impl Default for SerializerBuilder {
         Self {
             optimize_containers: false,
             max_depth: UbjsonSerializer::<std::io::Sink>::DEFAULT_MAX_DEPTH,
+            strict: false,
         }
     }
 }
@@ -203,9 +307,19 @@ impl SerializerBuilder {
         self
     }
 
+    /// Refuse to produce encodings that other draft-12 implementations may reject.
+    ///
+    /// In strict mode strongly-typed containers must carry a count and chars
+    /// must be ASCII; anything else fails with [`UbjsonError::NonConformant`].
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     /// Build a serializer with the configured options for the given writer.
     pub fn build<W: std::io::Write>(self, writer: W) -> UbjsonSerializer<W> {
         UbjsonSerializer::with_settings(writer, self.optimize_containers, self.max_depth)
+            .with_strict(self.strict)
     }
 
     /// Serialize a value to a Vec<u8> using the configured options.
@@ -245,6 +359,70 @@ impl SerializerBuilder {
         let mut serializer = self.build(writer);
         serializer.serialize_value(value)
     }
+
+    /// Serialize a value into `buffer` using the configured options,
+    /// returning the number of bytes written.
+    ///
+    /// If the encoding doesn't fit, this fails with
+    /// [`UbjsonError::BufferTooSmall`] giving the size it needs, and the
+    /// contents of `buffer` are unspecified.
+    #[cfg(feature = "serde")]
+    pub fn to_slice<T>(self, buffer: &mut [u8], value: &T) -> Result<usize>
+    where
+        T: serde::Serialize,
+    {
+        let options = self.clone();
+        write_slice(buffer, |writer| self.to_writer(writer, value), || options.encoded_len(value))
+    }
+
+    /// Serialize a UbjsonValue into `buffer` using the configured options,
+    /// returning the number of bytes written.
+    ///
+    /// If the encoding doesn't fit, this fails with
+    /// [`UbjsonError::BufferTooSmall`] giving the size it needs, and the
+    /// contents of `buffer` are unspecified.
+    pub fn value_to_slice(self, buffer: &mut [u8], value: &UbjsonValue) -> Result<usize> {
+        let options = self.clone();
+        write_slice(buffer, |writer| self.value_to_writer(writer, value), || options.value_encoded_len(value))
+    }
+
+    /// Compute the number of bytes [`to_vec`](Self::to_vec) would produce
+    /// with the configured options, without writing them.
+    #[cfg(feature = "serde")]
+    pub fn encoded_len<T>(self, value: &T) -> Result<usize>
+    where
+        T: serde::Serialize,
+    {
+        let mut counter = serializer::ByteCounter::default();
+        self.to_writer(&mut counter, value)?;
+        Ok(counter.count())
+    }
+
+    /// Compute the number of bytes [`value_to_vec`](Self::value_to_vec)
+    /// would produce with the configured options, without writing them.
+    pub fn value_encoded_len(self, value: &UbjsonValue) -> Result<usize> {
+        let mut counter = serializer::ByteCounter::default();
+        self.value_to_writer(&mut counter, value)?;
+        Ok(counter.count())
+    }
+}
+
+/// Run `write` against `buffer`, returning the number of bytes it wrote.
+/// Running out of space is reported with the size from `needed`.
+fn write_slice<W, N>(buffer: &mut [u8], write: W, needed: N) -> Result<usize>
+where
+    W: FnOnce(&mut &mut [u8]) -> Result<()>,
+    N: FnOnce() -> Result<usize>,
+{
+    let capacity = buffer.len();
+    let mut remaining = buffer;
+    match write(&mut remaining) {
+        Ok(()) => Ok(capacity - remaining.len()),
+        Err(UbjsonError::Io(e)) if e.kind() == std::io::ErrorKind::WriteZero => {
+            Err(UbjsonError::BufferTooSmall { needed: needed()? })
+        }
+        Err(e) => Err(e),
+    }
 }
 
 /// Builder for configuring UBJSON deserialization options.
@@ -252,6 +430,11 @@ impl SerializerBuilder {
 pub struct DeserializerBuilder {
     max_depth: usize,
     max_size: usize,
+    budget: deserializer::Budget,
+    allow_trailing_data: bool,
+    strict: bool,
+    lenient: bool,
+    duplicate_keys: DuplicateKeys,
 }
 
 impl Default for DeserializerBuilder {
@@ -259,6 +442,11 @@ impl Default for DeserializerBuilder {
         Self {
             max_depth: 1000,
             max_size: 1_000_000,
+            budget: deserializer::Budget::UNLIMITED,
+            allow_trailing_data: false,
+            strict: false,
+            lenient: false,
+            duplicate_keys: DuplicateKeys::Error,
         }
     }
 }
@@ -281,18 +469,134 @@ impl DeserializerBuilder {
         self
     }
 
+    /// Set the maximum number of input bytes read across the whole decode.
+    pub fn with_max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.budget.total_bytes = max_total_bytes;
+        self
+    }
+
+    /// Set the maximum number of values decoded across the whole decode.
+    ///
+    /// Containers and every element or object value inside them each count as one value.
+    pub fn with_max_total_values(mut self, max_total_values: usize) -> Self {
+        self.budget.total_values = max_total_values;
+        self
+    }
+
+    /// Set the maximum combined size in bytes of all strings, object keys and
+    /// high-precision numbers across the whole decode.
+    pub fn with_max_total_string_bytes(mut self, max_total_string_bytes: usize) -> Self {
+        self.budget.total_string_bytes = max_total_string_bytes;
+        self
+    }
+
+    /// Set the maximum size in bytes of a single string, object key or high-precision number.
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.budget.string_length = max_string_length;
+        self
+    }
+
+    /// Allow bytes after the value when deserializing from a byte slice.
+    ///
+    /// By default `from_slice` and `value_from_slice` fail with
+    /// [`UbjsonError::TrailingData`] if the value does not use the whole slice.
+    pub fn with_allow_trailing_data(mut self, allow: bool) -> Self {
+        self.allow_trailing_data = allow;
+        self
+    }
+
+    /// Reject input that does not conform to UBJSON draft 12.
+    ///
+    /// In strict mode strongly-typed containers without a `#` count and
+    /// multi-byte chars fail with [`UbjsonError::NonConformant`]. Typed element
+    /// types that aren't primitive are rejected whether or not this is set.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Accept common deviations from the specification and record them as warnings.
+    ///
+    /// In lenient mode duplicate keys (keeping the last value unless
+    /// [`with_duplicate_keys`](Self::with_duplicate_keys) says otherwise) and
+    /// no-op markers inside objects are accepted. These, uncounted typed containers, multi-byte
+    /// chars and high-precision numbers with a leading `+` are all recorded as
+    /// [`Warning`]s, available from [`UbjsonDeserializer::warnings`].
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Set how objects with repeated keys are handled, for both
+    /// [`UbjsonValue`] and serde map decoding. The default is [`DuplicateKeys::Error`].
+    pub fn with_duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
     /// Build a deserializer with the configured options for the given reader.
-    pub fn build<R: std::io::Read>(self, reader: R) -> UbjsonDeserializer<R> {
+    pub fn build<R: std::io::Read>(self, reader: R) -> UbjsonDeserializer<read::IoRead<R>> {
         UbjsonDeserializer::with_limits(reader, self.max_depth, self.max_size)
+            .with_budget(self.budget)
+            .with_strict(self.strict)
+            .with_lenient(self.lenient)
+            .with_duplicate_keys(self.duplicate_keys)
+    }
+
+    /// Build a deserializer with the configured options for the given byte slice.
+    pub fn build_slice(self, slice: &[u8]) -> UbjsonDeserializer<read::SliceRead<'_>> {
+        UbjsonDeserializer::from_read_with_limits(
+            read::SliceRead::new(slice),
+            self.max_depth,
+            self.max_size,
+        )
+        .with_budget(self.budget)
+        .with_strict(self.strict)
+        .with_lenient(self.lenient)
+        .with_duplicate_keys(self.duplicate_keys)
     }
 
     /// Deserialize a value from a byte slice using the configured options.
     #[cfg(feature = "serde")]
-    pub fn from_slice<'a, T>(self, slice: &'a [u8]) -> Result<T>
+    pub fn from_slice<T>(self, slice: &[u8]) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let allow_trailing_data = self.allow_trailing_data;
+        let mut deserializer = self.build_slice(slice);
+        let value = T::deserialize(&mut deserializer)?;
+        if !allow_trailing_data {
+            deserializer.end()?;
+        }
+        Ok(value)
+    }
+
+    /// Deserialize the first value in a byte slice using the configured options,
+    /// returning it with the number of bytes it used.
+    #[cfg(feature = "serde")]
+    pub fn from_slice_partial<T>(self, slice: &[u8]) -> Result<(T, usize)>
     where
         T: serde::de::DeserializeOwned,
     {
-        self.from_reader(slice)
+        let mut deserializer = self.build_slice(slice);
+        let value = T::deserialize(&mut deserializer)?;
+        Ok((value, deserializer.position()))
+    }
+
+    /// Deserialize a value from a byte slice using the configured options,
+    /// returning it with the warnings recorded in lenient mode.
+    #[cfg(feature = "serde")]
+    pub fn from_slice_with_warnings<T>(self, slice: &[u8]) -> Result<(T, Vec<Warning>)>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let allow_trailing_data = self.allow_trailing_data;
+        let mut deserializer = self.build_slice(slice);
+        let value = T::deserialize(&mut deserializer)?;
+        if !allow_trailing_data {
+            deserializer.end()?;
+        }
+        Ok((value, deserializer.take_warnings()))
     }
 
     /// Deserialize a value from a reader using the configured options.
@@ -308,7 +612,33 @@ impl DeserializerBuilder {
 
     /// Deserialize a UbjsonValue from a byte slice using the configured options.
     pub fn value_from_slice(self, slice: &[u8]) -> Result<UbjsonValue> {
-        self.value_from_reader(slice)
+        let allow_trailing_data = self.allow_trailing_data;
+        let mut deserializer = self.build_slice(slice);
+        let value = deserializer.deserialize_value()?;
+        if !allow_trailing_data {
+            deserializer.end()?;
+        }
+        Ok(value)
+    }
+
+    /// Deserialize the first UbjsonValue in a byte slice using the configured
+    /// options, returning it with the number of bytes it used.
+    pub fn value_from_slice_partial(self, slice: &[u8]) -> Result<(UbjsonValue, usize)> {
+        let mut deserializer = self.build_slice(slice);
+        let value = deserializer.deserialize_value()?;
+        Ok((value, deserializer.position()))
+    }
+
+    /// Deserialize a UbjsonValue from a byte slice using the configured options,
+    /// returning it with the warnings recorded in lenient mode.
+    pub fn value_from_slice_with_warnings(self, slice: &[u8]) -> Result<(UbjsonValue, Vec<Warning>)> {
+        let allow_trailing_data = self.allow_trailing_data;
+        let mut deserializer = self.build_slice(slice);
+        let value = deserializer.deserialize_value()?;
+        if !allow_trailing_data {
+            deserializer.end()?;
+        }
+        Ok((value, deserializer.take_warnings()))
     }
 
     /// Deserialize a UbjsonValue from a reader using the configured options.
@@ -319,4 +649,64 @@ impl DeserializerBuilder {
         let mut deserializer = self.build(reader);
         deserializer.deserialize_value()
     }
+
+    /// Deserialize a UbjsonValue from a reader using the configured options,
+    /// returning it with the warnings recorded in lenient mode.
+    pub fn value_from_reader_with_warnings<R>(self, reader: R) -> Result<(UbjsonValue, Vec<Warning>)>
+    where
+        R: std::io::Read,
+    {
+        let mut deserializer = self.build(reader);
+        let value = deserializer.deserialize_value()?;
+        Ok((value, deserializer.take_warnings()))
+    }
+
+    /// Stream the values matching `query` out of a byte slice using the
+    /// configured options, calling `f` with each one and its path.
+    ///
+    /// See [`UbjsonDeserializer::extract`] for what is decoded.
+    pub fn extract_from_slice<F>(self, slice: &[u8], query: &Query, f: F) -> Result<()>
+    where
+        F: FnMut(&[PathSegment], UbjsonValue),
+    {
+        let allow_trailing_data = self.allow_trailing_data;
+        let mut deserializer = self.build_slice(slice);
+        deserializer.extract(query, f)?;
+        if !allow_trailing_data {
+            deserializer.end()?;
+        }
+        Ok(())
+    }
+
+    /// Stream the values matching `query` out of a reader using the
+    /// configured options, calling `f` with each one and its path.
+    ///
+    /// See [`UbjsonDeserializer::extract`] for what is decoded.
+    pub fn extract_from_reader<R, F>(self, reader: R, query: &Query, f: F) -> Result<()>
+    where
+        R: std::io::Read,
+        F: FnMut(&[PathSegment], UbjsonValue),
+    {
+        let mut deserializer = self.build(reader);
+        deserializer.extract(query, f)
+    }
+
+    /// Decode as much as possible from a possibly damaged byte slice.
+    ///
+    /// See [`UbjsonDeserializer::recover_values`] for how errors are handled.
+    pub fn recover_from_slice(self, slice: &[u8]) -> PartialDocument {
+        let mut deserializer = self.build_slice(slice);
+        deserializer.recover_values()
+    }
+
+    /// Decode as much as possible from a possibly damaged reader.
+    ///
+    /// See [`UbjsonDeserializer::recover_values`] for how errors are handled.
+    pub fn recover_from_reader<R>(self, reader: R) -> PartialDocument
+    where
+        R: std::io::Read,
+    {
+        let mut deserializer = self.build(reader);
+        deserializer.recover_values()
+    }
 }
\ No newline at end of file
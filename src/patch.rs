@@ -0,0 +1,406 @@
+//! JSON Merge Patch (RFC 7396) and JSON Patch (RFC 6902) for [`UbjsonValue`].
+//!
+//! Both kinds of patch can be stored as UBJSON themselves: a merge patch is
+//! just a value, and a [`Patch`] converts to and from an array of operation
+//! objects such as `{"op": "add", "path": "/a", "value": 1}`.
+//!
+//! Strongly-typed containers keep their type when a patch adds or replaces
+//! values in them, as long as each new value has the container's type or is
+//! an integer that fits it. Otherwise the container becomes a plain one.
+
+use std::collections::HashMap;
+use crate::error::{Result, UbjsonError};
+use crate::types::UbjsonType;
+use crate::value::UbjsonValue;
+
+/// One JSON Patch operation. Paths are JSON Pointers (RFC 6901).
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOperation {
+    /// Insert a value into an object or array, or replace the whole document.
+    Add { path: String, value: UbjsonValue },
+    /// Remove the value at `path`.
+    Remove { path: String },
+    /// Replace the existing value at `path`.
+    Replace { path: String, value: UbjsonValue },
+    /// Remove the value at `from` and add it at `path`.
+    Move { from: String, path: String },
+    /// Add a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+    /// Check that the value at `path` is semantically equal to `value`.
+    Test { path: String, value: UbjsonValue },
+}
+
+/// A sequence of JSON Patch operations, applied all or nothing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Patch(pub Vec<PatchOperation>);
+
+impl UbjsonValue {
+    /// Apply a JSON Merge Patch (RFC 7396).
+    ///
+    /// Object members of `patch` are merged recursively, null members remove
+    /// keys, and any other patch replaces the value.
+    pub fn merge(&mut self, patch: &UbjsonValue) {
+        let Some(members) = patch.as_object() else {
+            *self = patch.clone();
+            return;
+        };
+        if !self.is_object() {
+            *self = UbjsonValue::Object(HashMap::new());
+        }
+        for (key, member) in members {
+            let pairs = self.as_object_mut().expect("target is an object");
+            if member.is_null() {
+                pairs.remove(key);
+                continue;
+            }
+            let mut child = pairs.remove(key).unwrap_or(UbjsonValue::Null);
+            child.merge(member);
+            let child = fit_container(self, child);
+            self.as_object_mut().expect("target is an object").insert(key.clone(), child);
+        }
+        update_count(self);
+    }
+
+    /// Apply a JSON Patch (RFC 6902).
+    ///
+    /// If any operation fails, the value is left unchanged and the error
+    /// names the failing operation.
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<()> {
+        let mut result = self.clone();
+        for (index, operation) in patch.0.iter().enumerate() {
+            result
+                .apply_operation(operation)
+                .map_err(|reason| UbjsonError::PatchFailed { index, reason })?;
+        }
+        *self = result;
+        Ok(())
+    }
+
+    /// Look up a value by JSON Pointer, such as `/items/0/name`.
+    pub fn pointer(&self, pointer: &str) -> Option<&UbjsonValue> {
+        let mut value = self;
+        for token in parse_pointer(pointer).ok()? {
+            value = match value {
+                UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+                    elements.get(parse_index(&token, elements.len(), false).ok()?)?
+                }
+                UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => pairs.get(&token)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Look up a value by JSON Pointer for modification.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut UbjsonValue> {
+        let mut value = self;
+        for token in parse_pointer(pointer).ok()? {
+            value = value.pointer_child(&token)?;
+        }
+        Some(value)
+    }
+
+    fn apply_operation(&mut self, operation: &PatchOperation) -> std::result::Result<(), String> {
+        match operation {
+            PatchOperation::Add { path, value } => self.patch_add(path, value.clone()),
+            PatchOperation::Remove { path } => self.patch_remove(path).map(drop),
+            PatchOperation::Replace { path, value } => {
+                let (parent, token) = self.patch_parent(path)?;
+                let Some(parent) = parent else {
+                    *self = value.clone();
+                    return Ok(());
+                };
+                if parent.pointer_child(&token).is_none() {
+                    return Err(format!("{} does not exist", path));
+                }
+                let value = fit_container(parent, value.clone());
+                *parent.pointer_child(&token).expect("checked above") = value;
+                Ok(())
+            }
+            PatchOperation::Move { from, path } => {
+                if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                    return Err(format!("cannot move {} into its own child {}", from, path));
+                }
+                let value = self.patch_remove(from)?;
+                self.patch_add(path, value)
+            }
+            PatchOperation::Copy { from, path } => {
+                let value = self.pointer(from).ok_or_else(|| format!("{} does not exist", from))?.clone();
+                self.patch_add(path, value)
+            }
+            PatchOperation::Test { path, value } => match self.pointer(path) {
+                Some(actual) if actual.semantic_eq(value) => Ok(()),
+                Some(_) => Err(format!("test failed at {}", path)),
+                None => Err(format!("{} does not exist", path)),
+            },
+        }
+    }
+
+    /// Find the container holding `path`, or `None` if `path` is the root.
+    fn patch_parent(&mut self, path: &str) -> std::result::Result<(Option<&mut UbjsonValue>, String), String> {
+        let mut tokens = parse_pointer(path)?;
+        let Some(last) = tokens.pop() else {
+            return Ok((None, String::new()));
+        };
+        let mut parent = self;
+        for token in &tokens {
+            parent = parent
+                .pointer_child(token)
+                .ok_or_else(|| format!("parent of {} does not exist", path))?;
+        }
+        Ok((Some(parent), last))
+    }
+
+    fn pointer_child(&mut self, token: &str) -> Option<&mut UbjsonValue> {
+        match self {
+            UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+                let index = parse_index(token, elements.len(), false).ok()?;
+                elements.get_mut(index)
+            }
+            UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => pairs.get_mut(token),
+            _ => None,
+        }
+    }
+
+    fn patch_add(&mut self, path: &str, value: UbjsonValue) -> std::result::Result<(), String> {
+        let (parent, token) = self.patch_parent(path)?;
+        let Some(parent) = parent else {
+            *self = value;
+            return Ok(());
+        };
+        if !parent.is_array() && !parent.is_object() {
+            return Err(format!("parent of {} is not a container", path));
+        }
+        let value = fit_container(parent, value);
+        match parent {
+            UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+                let index = parse_index(&token, elements.len(), true)?;
+                elements.insert(index, value);
+            }
+            UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => {
+                pairs.insert(token, value);
+            }
+            _ => unreachable!("checked above"),
+        }
+        update_count(parent);
+        Ok(())
+    }
+
+    fn patch_remove(&mut self, path: &str) -> std::result::Result<UbjsonValue, String> {
+        let (parent, token) = self.patch_parent(path)?;
+        let parent = parent.ok_or("cannot remove the root value")?;
+        let removed = match parent {
+            UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+                let index = parse_index(&token, elements.len(), false)?;
+                if index >= elements.len() {
+                    return Err(format!("{} does not exist", path));
+                }
+                elements.remove(index)
+            }
+            UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => {
+                pairs.remove(&token).ok_or_else(|| format!("{} does not exist", path))?
+            }
+            _ => return Err(format!("parent of {} is not a container", path)),
+        };
+        update_count(parent);
+        Ok(removed)
+    }
+}
+
+/// Split a JSON Pointer into unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> std::result::Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!("JSON Pointer {:?} must start with '/'", pointer));
+    };
+    rest.split('/')
+        .map(|token| {
+            let mut out = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    out.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => out.push('~'),
+                    Some('1') => out.push('/'),
+                    _ => return Err(format!("invalid escape in JSON Pointer {:?}", pointer)),
+                }
+            }
+            Ok(out)
+        })
+        .collect()
+}
+
+/// Parse an array index token. `-` means the end of the array when `allow_end` is set.
+fn parse_index(token: &str, len: usize, allow_end: bool) -> std::result::Result<usize, String> {
+    if token == "-" && allow_end {
+        return Ok(len);
+    }
+    let valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    let index = token
+        .parse::<usize>()
+        .ok()
+        .filter(|_| valid)
+        .ok_or_else(|| format!("invalid array index {:?}", token))?;
+    if index > len || (index == len && !allow_end) {
+        return Err(format!("array index {} is out of bounds", index));
+    }
+    Ok(index)
+}
+
+/// Prepare `value` to go into `container`. The value is converted to the
+/// container's element type if it fits, or else the container is flattened.
+fn fit_container(container: &mut UbjsonValue, value: UbjsonValue) -> UbjsonValue {
+    let element_type = match container {
+        UbjsonValue::StronglyTypedArray { element_type, .. } => *element_type,
+        UbjsonValue::StronglyTypedObject { value_type, .. } => *value_type,
+        _ => return value,
+    };
+    match coerce(value, element_type) {
+        Ok(value) => value,
+        Err(value) => {
+            match container {
+                UbjsonValue::StronglyTypedArray { elements, .. } => {
+                    *container = UbjsonValue::Array(std::mem::take(elements));
+                }
+                UbjsonValue::StronglyTypedObject { pairs, .. } => {
+                    *container = UbjsonValue::Object(std::mem::take(pairs));
+                }
+                _ => {}
+            }
+            value
+        }
+    }
+}
+
+/// Convert `value` to `element_type` without losing information, or hand it
+/// back if that isn't possible.
+pub(crate) fn coerce(value: UbjsonValue, element_type: UbjsonType) -> std::result::Result<UbjsonValue, UbjsonValue> {
+    if value.get_type() == element_type {
+        // A typed char container only holds single-byte chars
+        return match value {
+            UbjsonValue::Char(c) if !c.is_ascii() => Err(value),
+            value => Ok(value),
+        };
+    }
+    match (&value, element_type) {
+        (UbjsonValue::Float32(f), UbjsonType::Float64) => return Ok(UbjsonValue::Float64(*f as f64)),
+        (UbjsonValue::Float64(f), UbjsonType::Float32) if *f as f32 as f64 == *f || f.is_nan() => {
+            return Ok(UbjsonValue::Float32(*f as f32));
+        }
+        _ => {}
+    }
+    let fitted = value.as_i64().filter(|_| value.is_integer()).and_then(|n| match element_type {
+        UbjsonType::Int8 => i8::try_from(n).ok().map(UbjsonValue::Int8),
+        UbjsonType::UInt8 => u8::try_from(n).ok().map(UbjsonValue::UInt8),
+        UbjsonType::Int16 => i16::try_from(n).ok().map(UbjsonValue::Int16),
+        UbjsonType::Int32 => i32::try_from(n).ok().map(UbjsonValue::Int32),
+        UbjsonType::Int64 => Some(UbjsonValue::Int64(n)),
+        // Only when the float holds the integer exactly; i128 avoids saturating casts
+        UbjsonType::Float32 => Some(n as f32).filter(|f| *f as i128 == n as i128).map(UbjsonValue::Float32),
+        UbjsonType::Float64 => Some(n as f64).filter(|f| *f as i128 == n as i128).map(UbjsonValue::Float64),
+        _ => None,
+    });
+    fitted.ok_or(value)
+}
+
+fn update_count(container: &mut UbjsonValue) {
+    match container {
+        UbjsonValue::StronglyTypedArray { count: Some(count), elements, .. } => *count = elements.len(),
+        UbjsonValue::StronglyTypedObject { count: Some(count), pairs, .. } => *count = pairs.len(),
+        _ => {}
+    }
+}
+
+impl PatchOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            PatchOperation::Add { .. } => "add",
+            PatchOperation::Remove { .. } => "remove",
+            PatchOperation::Replace { .. } => "replace",
+            PatchOperation::Move { .. } => "move",
+            PatchOperation::Copy { .. } => "copy",
+            PatchOperation::Test { .. } => "test",
+        }
+    }
+}
+
+impl From<&PatchOperation> for UbjsonValue {
+    fn from(operation: &PatchOperation) -> Self {
+        let mut pairs = HashMap::new();
+        pairs.insert("op".to_string(), UbjsonValue::from(operation.name()));
+        match operation {
+            PatchOperation::Add { path, value }
+            | PatchOperation::Replace { path, value }
+            | PatchOperation::Test { path, value } => {
+                pairs.insert("path".to_string(), UbjsonValue::from(path.as_str()));
+                pairs.insert("value".to_string(), value.clone());
+            }
+            PatchOperation::Remove { path } => {
+                pairs.insert("path".to_string(), UbjsonValue::from(path.as_str()));
+            }
+            PatchOperation::Move { from, path } | PatchOperation::Copy { from, path } => {
+                pairs.insert("from".to_string(), UbjsonValue::from(from.as_str()));
+                pairs.insert("path".to_string(), UbjsonValue::from(path.as_str()));
+            }
+        }
+        UbjsonValue::Object(pairs)
+    }
+}
+
+impl TryFrom<&UbjsonValue> for PatchOperation {
+    type Error = UbjsonError;
+
+    fn try_from(value: &UbjsonValue) -> Result<Self> {
+        let pairs = value
+            .as_object()
+            .ok_or_else(|| UbjsonError::InvalidPatch(format!("operation must be an object, found {}", value.type_name())))?;
+        let text = |key: &str| -> Result<String> {
+            match pairs.get(key) {
+                Some(UbjsonValue::String(s)) => Ok(s.clone()),
+                Some(UbjsonValue::Char(c)) => Ok(c.to_string()),
+                Some(other) => Err(UbjsonError::InvalidPatch(format!("\"{}\" must be a string, found {}", key, other.type_name()))),
+                None => Err(UbjsonError::InvalidPatch(format!("operation is missing \"{}\"", key))),
+            }
+        };
+        let value = || {
+            pairs
+                .get("value")
+                .cloned()
+                .ok_or_else(|| UbjsonError::InvalidPatch("operation is missing \"value\"".to_string()))
+        };
+        let path = text("path")?;
+        Ok(match text("op")?.as_str() {
+            "add" => PatchOperation::Add { path, value: value()? },
+            "remove" => PatchOperation::Remove { path },
+            "replace" => PatchOperation::Replace { path, value: value()? },
+            "move" => PatchOperation::Move { from: text("from")?, path },
+            "copy" => PatchOperation::Copy { from: text("from")?, path },
+            "test" => PatchOperation::Test { path, value: value()? },
+            other => return Err(UbjsonError::InvalidPatch(format!("unknown operation {:?}", other))),
+        })
+    }
+}
+
+impl From<&Patch> for UbjsonValue {
+    fn from(patch: &Patch) -> Self {
+        UbjsonValue::Array(patch.0.iter().map(UbjsonValue::from).collect())
+    }
+}
+
+impl TryFrom<&UbjsonValue> for Patch {
+    type Error = UbjsonError;
+
+    fn try_from(value: &UbjsonValue) -> Result<Self> {
+        let operations = value
+            .as_array()
+            .ok_or_else(|| UbjsonError::InvalidPatch(format!("patch must be an array, found {}", value.type_name())))?;
+        operations.iter().map(PatchOperation::try_from).collect::<Result<_>>().map(Patch)
+    }
+}
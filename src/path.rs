@@ -0,0 +1,50 @@
+//! Locations of values inside a document.
+
+use std::fmt;
+
+/// One step from a container to one of its children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// An object entry.
+    Key(String),
+    /// An array element.
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) if is_identifier(key) => write!(f, ".{}", key),
+            PathSegment::Key(key) => write!(f, "[{:?}]", key),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+/// Format a path as `$.key[0]["other key"]`, where `$` is the root value.
+pub fn format_path(path: &[PathSegment]) -> String {
+    let mut out = String::from("$");
+    for segment in path {
+        out.push_str(&segment.to_string());
+    }
+    out
+}
+
+/// Format a path as a JSON Pointer (RFC 6901), such as `/key/0`.
+pub fn format_pointer(path: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        out.push('/');
+        match segment {
+            PathSegment::Key(key) => out.push_str(&key.replace('~', "~0").replace('/', "~1")),
+            PathSegment::Index(index) => out.push_str(&index.to_string()),
+        }
+    }
+    out
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,626 @@
+//! JSONPath-style queries over [`UbjsonValue`].
+//!
+//! A practical subset of JSONPath (RFC 9535) is supported:
+//!
+//! - `$` for the root value, which may be left out: `players[0]` means `$.players[0]`
+//! - `.name`, `['name']` and `["name"]` for object members
+//! - `.*` and `[*]` for every child of an array or object
+//! - `..` for recursive descent, as in `$..damage` or `$..[0]`
+//! - `[1]` and `[-1]` for array elements, counted from the end when negative
+//! - `[start:end:step]` for array slices, with Python semantics
+//! - `[0, 2]` and `['a', 'b']` for unions of selectors
+//! - `[?(@.port == 2)]` for filters, with `==`, `!=`, `<`, `<=`, `>`, `>=`,
+//!   `&&`, `||`, `!` and parentheses. `[?(@.name)]` tests whether a member
+//!   exists, and `$` in a filter refers to the root value.
+//!
+//! Typed containers are queried like plain ones. Equality in filters is
+//! semantic, so `@.port == 2` matches `Int8(2)` and `Float64(2.0)` alike.
+//! Ordering comparisons only hold between two numbers or two strings.
+//!
+//! Results are in document order, with object members in key order.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+use crate::error::{Result, UbjsonError};
+use crate::path::PathSegment;
+use crate::value::UbjsonValue;
+
+/// A parsed query expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub(crate) segments: Vec<Segment>,
+}
+
+/// One step of a query, applied to every value selected so far.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    /// Select children of each value.
+    Child(Vec<Selector>),
+    /// Select children of each value and of all its descendants.
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice { start: Option<i64>, end: Option<i64>, step: i64 },
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Filter {
+    Exists(Operand),
+    Compare(Operand, Comparison, Operand),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+/// A value in a filter. Paths only hold `Name` and `Index` selectors.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Operand {
+    Current(Vec<Selector>),
+    Root(Vec<Selector>),
+    Literal(UbjsonValue),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Query {
+    /// Parse a query expression.
+    ///
+    /// Malformed expressions fail with [`UbjsonError::InvalidQuery`].
+    pub fn parse(expr: &str) -> Result<Query> {
+        let mut parser = Parser { input: expr, pos: 0 };
+        let segments = parser.query()?;
+        Ok(Query { segments })
+    }
+
+    /// Select every value matching the query.
+    pub fn select<'a>(&self, value: &'a UbjsonValue) -> Vec<&'a UbjsonValue> {
+        self.select_paths(value).into_iter().map(|(_, value)| value).collect()
+    }
+
+    /// Select every value matching the query, with its path from `value`.
+    pub fn select_paths<'a>(&self, value: &'a UbjsonValue) -> Vec<(Vec<PathSegment>, &'a UbjsonValue)> {
+        select_segments(&self.segments, value)
+    }
+
+    /// Whether a filter in the query refers to the root value with `$`.
+    pub(crate) fn refers_to_root(&self) -> bool {
+        fn refers(filter: &Filter) -> bool {
+            match filter {
+                Filter::Exists(operand) => matches!(operand, Operand::Root(_)),
+                Filter::Compare(left, _, right) => {
+                    matches!(left, Operand::Root(_)) || matches!(right, Operand::Root(_))
+                }
+                Filter::And(a, b) | Filter::Or(a, b) => refers(a) || refers(b),
+                Filter::Not(filter) => refers(filter),
+            }
+        }
+        self.segments
+            .iter()
+            .flat_map(Segment::selectors)
+            .any(|selector| matches!(selector, Selector::Filter(filter) if refers(filter)))
+    }
+}
+
+impl Segment {
+    pub(crate) fn selectors(&self) -> &[Selector] {
+        match self {
+            Segment::Child(selectors) | Segment::Descendant(selectors) => selectors,
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = UbjsonError;
+
+    fn from_str(expr: &str) -> Result<Query> {
+        Query::parse(expr)
+    }
+}
+
+impl UbjsonValue {
+    /// Select every value inside this one matching a query expression.
+    ///
+    /// See [`crate::query`] for the supported syntax.
+    pub fn query(&self, expr: &str) -> Result<Vec<&UbjsonValue>> {
+        Ok(Query::parse(expr)?.select(self))
+    }
+}
+
+/// Apply `segments` in turn, starting from `value` as the root.
+pub(crate) fn select_segments<'a>(
+    segments: &[Segment],
+    value: &'a UbjsonValue,
+) -> Vec<(Vec<PathSegment>, &'a UbjsonValue)> {
+    let mut nodes = vec![(Vec::new(), value)];
+    for segment in segments {
+        let mut next = Vec::new();
+        for (path, node) in nodes {
+            match segment {
+                Segment::Child(selectors) => select_children(value, &path, node, selectors, &mut next),
+                Segment::Descendant(selectors) => {
+                    for (path, node) in descendants(path, node) {
+                        select_children(value, &path, node, selectors, &mut next);
+                    }
+                }
+            }
+        }
+        nodes = next;
+    }
+    nodes
+}
+
+fn select_children<'a>(
+    root: &'a UbjsonValue,
+    path: &[PathSegment],
+    node: &'a UbjsonValue,
+    selectors: &[Selector],
+    out: &mut Vec<(Vec<PathSegment>, &'a UbjsonValue)>,
+) {
+    let mut push = |segment: PathSegment, child: &'a UbjsonValue| {
+        let mut path = path.to_vec();
+        path.push(segment);
+        out.push((path, child));
+    };
+    for selector in selectors {
+        match selector {
+            Selector::Name(name) => {
+                if let Some(child) = node.as_object().and_then(|pairs| pairs.get(name)) {
+                    push(PathSegment::Key(name.clone()), child);
+                }
+            }
+            Selector::Index(index) => {
+                if let Some(elements) = node.as_array()
+                    && let Some(index) = resolve_index(*index, elements.len())
+                {
+                    push(PathSegment::Index(index), &elements[index]);
+                }
+            }
+            Selector::Slice { start, end, step } => {
+                if let Some(elements) = node.as_array() {
+                    for index in slice_indices(*start, *end, *step, elements.len()) {
+                        push(PathSegment::Index(index), &elements[index]);
+                    }
+                }
+            }
+            Selector::Wildcard => {
+                for (segment, child) in children(node) {
+                    push(segment, child);
+                }
+            }
+            Selector::Filter(filter) => {
+                for (segment, child) in children(node) {
+                    if filter.matches(root, child) {
+                        push(segment, child);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The children of a container, array elements in order and object members in key order.
+fn children(node: &UbjsonValue) -> Vec<(PathSegment, &UbjsonValue)> {
+    if let Some(elements) = node.as_array() {
+        elements.iter().enumerate().map(|(index, child)| (PathSegment::Index(index), child)).collect()
+    } else if let Some(pairs) = node.as_object() {
+        let mut entries: Vec<_> = pairs.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter().map(|(key, child)| (PathSegment::Key(key.clone()), child)).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// A value and all values inside it, in document order.
+fn descendants(path: Vec<PathSegment>, node: &UbjsonValue) -> Vec<(Vec<PathSegment>, &UbjsonValue)> {
+    let mut out = Vec::new();
+    let mut stack = vec![(path, node)];
+    while let Some((path, node)) = stack.pop() {
+        for (segment, child) in children(node).into_iter().rev() {
+            let mut path = path.clone();
+            path.push(segment);
+            stack.push((path, child));
+        }
+        out.push((path, node));
+    }
+    out
+}
+
+pub(crate) fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+fn slice_indices(start: Option<i64>, end: Option<i64>, step: i64, len: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..len).filter(|&index| slice_contains(start, end, step, Some(len), index)).collect();
+    if step < 0 {
+        indices.reverse();
+    }
+    indices
+}
+
+/// Whether a slice selects `index`. Without `len` the result is only
+/// meaningful for slices with non-negative bounds and a positive step.
+pub(crate) fn slice_contains(start: Option<i64>, end: Option<i64>, step: i64, len: Option<usize>, index: usize) -> bool {
+    let len = len.map_or(i64::MAX, |len| len as i64);
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    let index = index as i64;
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = end.map_or(len, |end| normalize(end).clamp(0, len));
+        lower <= index && index < upper && (index - lower) % step == 0
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = end.map_or(-1, |end| normalize(end).clamp(-1, len - 1));
+        lower < index && index <= upper && (upper - index) % step == 0
+    } else {
+        false
+    }
+}
+
+impl Filter {
+    pub(crate) fn matches(&self, root: &UbjsonValue, current: &UbjsonValue) -> bool {
+        match self {
+            Filter::Exists(operand) => operand.resolve(root, current).is_some(),
+            Filter::Compare(left, comparison, right) => {
+                comparison.holds(left.resolve(root, current), right.resolve(root, current))
+            }
+            Filter::And(a, b) => a.matches(root, current) && b.matches(root, current),
+            Filter::Or(a, b) => a.matches(root, current) || b.matches(root, current),
+            Filter::Not(filter) => !filter.matches(root, current),
+        }
+    }
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, root: &'a UbjsonValue, current: &'a UbjsonValue) -> Option<&'a UbjsonValue> {
+        let (mut value, path) = match self {
+            Operand::Current(path) => (current, path),
+            Operand::Root(path) => (root, path),
+            Operand::Literal(value) => return Some(value),
+        };
+        for selector in path {
+            value = match selector {
+                Selector::Name(name) => value.as_object()?.get(name)?,
+                Selector::Index(index) => {
+                    let elements = value.as_array()?;
+                    &elements[resolve_index(*index, elements.len())?]
+                }
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+}
+
+impl Comparison {
+    /// Compare two operands. A missing value only equals another missing value.
+    fn holds(self, left: Option<&UbjsonValue>, right: Option<&UbjsonValue>) -> bool {
+        let (left, right) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            (None, None) => return matches!(self, Comparison::Eq | Comparison::Le | Comparison::Ge),
+            _ => return self == Comparison::Ne,
+        };
+        match self {
+            Comparison::Eq => left.semantic_eq(right),
+            Comparison::Ne => !left.semantic_eq(right),
+            _ if !(left.is_number() && right.is_number() || is_text(left) && is_text(right)) => false,
+            Comparison::Lt => left.semantic_cmp(right) == Ordering::Less,
+            Comparison::Le => left.semantic_cmp(right) != Ordering::Greater,
+            Comparison::Gt => left.semantic_cmp(right) == Ordering::Greater,
+            Comparison::Ge => left.semantic_cmp(right) != Ordering::Less,
+        }
+    }
+}
+
+fn is_text(value: &UbjsonValue) -> bool {
+    matches!(value, UbjsonValue::String(_) | UbjsonValue::Char(_))
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || !c.is_ascii()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: impl Into<String>) -> UbjsonError {
+        UbjsonError::InvalidQuery { position: self.pos, reason: reason.into() }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.input[self.pos..].starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", token)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn query(&mut self) -> Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+        if !self.eat("$") && self.peek().is_some_and(is_name_char) {
+            segments.push(Segment::Child(vec![Selector::Name(self.name()?)]));
+        }
+        while self.pos < self.input.len() {
+            segments.push(self.segment()?);
+        }
+        Ok(segments)
+    }
+
+    fn segment(&mut self) -> Result<Segment> {
+        if self.eat("..") {
+            let selectors = if self.peek() == Some('[') { self.bracket()? } else { vec![self.dot_selector()?] };
+            Ok(Segment::Descendant(selectors))
+        } else if self.eat(".") {
+            Ok(Segment::Child(vec![self.dot_selector()?]))
+        } else if self.peek() == Some('[') {
+            Ok(Segment::Child(self.bracket()?))
+        } else {
+            Err(self.error("expected '.', '..' or '['"))
+        }
+    }
+
+    fn dot_selector(&mut self) -> Result<Selector> {
+        if self.eat("*") {
+            Ok(Selector::Wildcard)
+        } else {
+            Ok(Selector::Name(self.name()?))
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_name_char) {
+            self.bump();
+        }
+        if start == self.pos {
+            return Err(self.error("expected a member name"));
+        }
+        Ok(self.input[start..self.pos].to_string())
+    }
+
+    fn bracket(&mut self) -> Result<Vec<Selector>> {
+        self.expect("[")?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if !self.eat(",") {
+                self.expect("]")?;
+                return Ok(selectors);
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector> {
+        match self.peek() {
+            Some('*') => {
+                self.bump();
+                Ok(Selector::Wildcard)
+            }
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('?') => {
+                self.bump();
+                Ok(Selector::Filter(self.or_filter()?))
+            }
+            _ => self.index_or_slice(),
+        }
+    }
+
+    fn index_or_slice(&mut self) -> Result<Selector> {
+        let start = self.integer()?;
+        self.skip_whitespace();
+        if !self.eat(":") {
+            return start.map(Selector::Index).ok_or_else(|| self.error("expected a selector"));
+        }
+        self.skip_whitespace();
+        let end = self.integer()?;
+        self.skip_whitespace();
+        let mut step = 1;
+        if self.eat(":") {
+            self.skip_whitespace();
+            step = self.integer()?.unwrap_or(1);
+        }
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    /// Parse an optional integer, such as an index or slice bound.
+    fn integer(&mut self) -> Result<Option<i64>> {
+        let start = self.pos;
+        self.eat("-");
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+        let text = &self.input[start..self.pos];
+        match text {
+            "" => Ok(None),
+            "-" => Err(self.error("expected a digit")),
+            _ => text.parse().map(Some).map_err(|_| self.error(format!("integer {} is out of range", text))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some(c) if Some(c) == quote => return Ok(out),
+                Some('\\') => out.push(match self.bump() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('u') => {
+                        let hex = self.input.get(self.pos..self.pos + 4).unwrap_or("");
+                        let c = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+                        let c = c.ok_or_else(|| self.error("invalid unicode escape"))?;
+                        self.pos += 4;
+                        c
+                    }
+                    Some(c @ ('\\' | '/' | '\'' | '"')) => c,
+                    _ => return Err(self.error("invalid escape")),
+                }),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn or_filter(&mut self) -> Result<Filter> {
+        let mut filter = self.and_filter()?;
+        while self.eat("||") {
+            filter = Filter::Or(Box::new(filter), Box::new(self.and_filter()?));
+        }
+        Ok(filter)
+    }
+
+    fn and_filter(&mut self) -> Result<Filter> {
+        let mut filter = self.unary_filter()?;
+        while self.eat("&&") {
+            filter = Filter::And(Box::new(filter), Box::new(self.unary_filter()?));
+        }
+        Ok(filter)
+    }
+
+    fn unary_filter(&mut self) -> Result<Filter> {
+        self.skip_whitespace();
+        let filter = if self.eat("!") {
+            Filter::Not(Box::new(self.unary_filter()?))
+        } else if self.eat("(") {
+            let filter = self.or_filter()?;
+            self.expect(")")?;
+            filter
+        } else {
+            self.comparison()?
+        };
+        self.skip_whitespace();
+        Ok(filter)
+    }
+
+    fn comparison(&mut self) -> Result<Filter> {
+        let start = self.pos;
+        let left = self.operand()?;
+        self.skip_whitespace();
+        let comparison = [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token));
+        match (comparison, left) {
+            (Some((_, comparison)), left) => {
+                self.skip_whitespace();
+                Ok(Filter::Compare(left, comparison, self.operand()?))
+            }
+            (None, Operand::Literal(_)) => {
+                self.pos = start;
+                Err(self.error("expected a path or a comparison"))
+            }
+            (None, path) => Ok(Filter::Exists(path)),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        match self.peek() {
+            Some('@') => {
+                self.bump();
+                Ok(Operand::Current(self.relative_path()?))
+            }
+            Some('$') => {
+                self.bump();
+                Ok(Operand::Root(self.relative_path()?))
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(UbjsonValue::String(self.string()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ if self.eat("true") => Ok(Operand::Literal(UbjsonValue::Bool(true))),
+            _ if self.eat("false") => Ok(Operand::Literal(UbjsonValue::Bool(false))),
+            _ if self.eat("null") => Ok(Operand::Literal(UbjsonValue::Null)),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Parse the `.name`, `['name']` and `[index]` steps after `@` or `$`.
+    fn relative_path(&mut self) -> Result<Vec<Selector>> {
+        let mut path = Vec::new();
+        loop {
+            if self.eat(".") {
+                path.push(Selector::Name(self.name()?));
+            } else if self.eat("[") {
+                self.skip_whitespace();
+                let selector = if matches!(self.peek(), Some('\'' | '"')) {
+                    Selector::Name(self.string()?)
+                } else {
+                    Selector::Index(self.integer()?.ok_or_else(|| self.error("expected a name or an index"))?)
+                };
+                self.skip_whitespace();
+                self.expect("]")?;
+                path.push(selector);
+            } else {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Operand> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            self.bump();
+        }
+        let text = &self.input[start..self.pos];
+        if let Ok(n) = text.parse::<i64>() {
+            Ok(Operand::Literal(UbjsonValue::Int64(n)))
+        } else if text.parse::<f64>().is_ok() {
+            Ok(Operand::Literal(UbjsonValue::HighPrecision(text.to_string())))
+        } else {
+            self.pos = start;
+            Err(self.error(format!("invalid number {}", text)))
+        }
+    }
+}
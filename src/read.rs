@@ -0,0 +1,291 @@
+//! Input sources for the UBJSON deserializer.
+//!
+//! The deserializer does not read from `std::io::Read` directly. Instead it is
+//! generic over the [`Read`] trait defined here, which adds one byte of lookahead
+//! and lets in-memory input hand out slices without copying:
+//!
+//! - [`SliceRead`] reads from a byte slice and borrows string payloads from it.
+//! - [`IoRead`] wraps any `std::io::Read` and buffers internally, so unbuffered
+//!   sources such as `File` or `TcpStream` are not read one byte per syscall.
+
+use std::io;
+use std::ops::Deref;
+use crate::error::{UbjsonError, Result};
+
+/// Size of the internal buffer used by [`IoRead`].
+const IO_BUFFER_SIZE: usize = 8 * 1024;
+
+/// A byte slice produced by [`Read::read_slice`].
+///
+/// Either borrowed from the underlying input for the `'b` lifetime, or copied
+/// into a scratch buffer (or an internal buffer) that lives for `'c`.
+#[derive(Debug)]
+pub enum Reference<'b, 'c, T: ?Sized + 'static> {
+    /// Borrowed directly from the input.
+    Borrowed(&'b T),
+    /// Copied into a temporary buffer.
+    Copied(&'c T),
+}
+
+impl<'b, 'c, T: ?Sized + 'static> Deref for Reference<'b, 'c, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match *self {
+            Reference::Borrowed(b) => b,
+            Reference::Copied(c) => c,
+        }
+    }
+}
+
+/// Byte source used by [`UbjsonDeserializer`](crate::UbjsonDeserializer).
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Read<'de>: private::Sealed {
+    /// Return the next byte without consuming it, or `None` at end of input.
+    fn peek(&mut self) -> Result<Option<u8>>;
+
+    /// Consume and return the next byte, or `None` at end of input.
+    fn next(&mut self) -> Result<Option<u8>>;
+
+    /// Consume a byte previously returned by [`peek`](Read::peek).
+    fn discard(&mut self);
+
+    /// Fill `buf` completely, failing if the input ends first.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()>;
+
+    /// Read exactly `len` bytes, borrowing from the input where possible and
+    /// falling back to `scratch` otherwise.
+    fn read_slice<'s>(
+        &'s mut self,
+        len: usize,
+        scratch: &'s mut Vec<u8>,
+    ) -> Result<Reference<'de, 's, [u8]>>;
+
+    /// Skip over exactly `len` bytes.
+    fn skip(&mut self, len: usize) -> Result<()>;
+
+    /// Number of bytes consumed from the start of the input.
+    fn position(&self) -> usize;
+}
+
+fn eof() -> UbjsonError {
+    UbjsonError::UnexpectedEof
+}
+
+/// Input source reading from an in-memory byte slice.
+pub struct SliceRead<'a> {
+    slice: &'a [u8],
+    index: usize,
+}
+
+impl<'a> SliceRead<'a> {
+    /// Create a new input source over the given slice.
+    pub fn new(slice: &'a [u8]) -> Self {
+        Self { slice, index: 0 }
+    }
+
+    /// The part of the input that has not been consumed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.slice[self.index..]
+    }
+}
+
+impl<'a> Read<'a> for SliceRead<'a> {
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.slice.get(self.index).copied())
+    }
+
+    fn next(&mut self) -> Result<Option<u8>> {
+        let byte = self.slice.get(self.index).copied();
+        if byte.is_some() {
+            self.index += 1;
+        }
+        Ok(byte)
+    }
+
+    fn discard(&mut self) {
+        self.index += 1;
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        let start = self.index;
+        self.skip(buf.len())?;
+        buf.copy_from_slice(&self.slice[start..self.index]);
+        Ok(())
+    }
+
+    fn read_slice<'s>(
+        &'s mut self,
+        len: usize,
+        _scratch: &'s mut Vec<u8>,
+    ) -> Result<Reference<'a, 's, [u8]>> {
+        if len > self.slice.len() - self.index {
+            self.index = self.slice.len();
+            return Err(eof());
+        }
+        let start = self.index;
+        self.index += len;
+        Ok(Reference::Borrowed(&self.slice[start..self.index]))
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        if len > self.slice.len() - self.index {
+            self.index = self.slice.len();
+            return Err(eof());
+        }
+        self.index += len;
+        Ok(())
+    }
+
+    fn position(&self) -> usize {
+        self.index
+    }
+}
+
+/// Input source wrapping a `std::io::Read` with an internal buffer.
+///
+/// Because input is read ahead in blocks, the wrapped reader may be advanced
+/// past the end of the last value that was deserialized. Those bytes stay in
+/// the buffer and can be recovered with [`into_inner`](IoRead::into_inner).
+pub struct IoRead<R: io::Read> {
+    reader: R,
+    buffer: Box<[u8]>,
+    pos: usize,
+    filled: usize,
+    consumed: usize,
+}
+
+impl<R: io::Read> IoRead<R> {
+    /// Create a new buffered input source around the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0u8; IO_BUFFER_SIZE].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Refill the buffer if it has been fully consumed.
+    /// Returns false if the underlying reader is at end of input.
+    fn fill(&mut self) -> Result<bool> {
+        if self.pos < self.filled {
+            return Ok(true);
+        }
+        self.consumed += self.filled;
+        self.pos = 0;
+        self.filled = 0;
+        loop {
+            match self.reader.read(&mut self.buffer) {
+                Ok(0) => return Ok(false),
+                Ok(n) => {
+                    self.filled = n;
+                    return Ok(true);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// The bytes read from the underlying reader but not consumed yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer[self.pos..self.filled]
+    }
+
+    /// Unwrap the underlying reader, along with the bytes read from it but
+    /// not consumed yet.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        let unread = self.buffer().to_vec();
+        (self.reader, unread)
+    }
+}
+
+impl<'de, R: io::Read> Read<'de> for IoRead<R> {
+    fn peek(&mut self) -> Result<Option<u8>> {
+        if !self.fill()? {
+            return Ok(None);
+        }
+        Ok(Some(self.buffer[self.pos]))
+    }
+
+    fn next(&mut self) -> Result<Option<u8>> {
+        let byte = self.peek()?;
+        if byte.is_some() {
+            self.pos += 1;
+        }
+        Ok(byte)
+    }
+
+    fn discard(&mut self) {
+        self.pos += 1;
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        let mut written = 0;
+        while written < buf.len() {
+            if !self.fill()? {
+                return Err(eof());
+            }
+            let available = self.buffer();
+            let n = available.len().min(buf.len() - written);
+            buf[written..written + n].copy_from_slice(&available[..n]);
+            written += n;
+            self.pos += n;
+        }
+        Ok(())
+    }
+
+    fn read_slice<'s>(
+        &'s mut self,
+        len: usize,
+        scratch: &'s mut Vec<u8>,
+    ) -> Result<Reference<'de, 's, [u8]>> {
+        if len == 0 {
+            return Ok(Reference::Copied(&[]));
+        }
+        if self.fill()? && self.buffer().len() >= len {
+            let start = self.pos;
+            self.pos += len;
+            return Ok(Reference::Copied(&self.buffer[start..start + len]));
+        }
+
+        scratch.clear();
+        while scratch.len() < len {
+            if !self.fill()? {
+                return Err(eof());
+            }
+            let available = self.buffer();
+            let n = available.len().min(len - scratch.len());
+            scratch.extend_from_slice(&available[..n]);
+            self.pos += n;
+        }
+        Ok(Reference::Copied(&scratch[..]))
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        let mut remaining = len;
+        while remaining > 0 {
+            if !self.fill()? {
+                return Err(eof());
+            }
+            let n = self.buffer().len().min(remaining);
+            self.pos += n;
+            remaining -= n;
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> usize {
+        self.consumed + self.pos
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::SliceRead<'_> {}
+    impl<R: std::io::Read> Sealed for super::IoRead<R> {}
+}
@@ -0,0 +1,306 @@
+//! Comparing values by meaning rather than by encoding.
+//!
+//! Two values are semantically equal when they hold the same logical data,
+//! whatever integer width, float width or container optimization was used to
+//! write them:
+//!
+//! - Numbers compare by numeric value, so `Int8(1)`, `Int64(1)`,
+//!   `Float64(1.0)` and `HighPrecision("1")` are all equal. `-0.0` equals `0`.
+//!   A high-precision number that doesn't parse as a number compares by its text.
+//! - A `Char` equals the one-character `String` holding the same character.
+//! - Strongly-typed arrays and objects equal plain ones with the same contents.
+//!
+//! The ordering is total. Kinds are ordered null < bool < number < string <
+//! array < object. NaN equals every other NaN and sorts after all other
+//! numbers, followed by unparsable high-precision numbers. Arrays compare
+//! element by element, and objects compare their entries sorted by key.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use crate::value::UbjsonValue;
+
+impl UbjsonValue {
+    /// Check whether two values hold the same logical data, ignoring encoding.
+    pub fn semantic_eq(&self, other: &UbjsonValue) -> bool {
+        self.semantic_cmp(other) == Ordering::Equal
+    }
+
+    /// Compare two values by logical data, using the total ordering described in [`crate::semantic`].
+    pub fn semantic_cmp(&self, other: &UbjsonValue) -> Ordering {
+        // Containers being compared, with how their lengths compare
+        let mut stack: Vec<(Children, Children, Ordering)> = Vec::new();
+        let (mut a, mut b) = (self, other);
+        loop {
+            let ordering = match (Kind::of(a), Kind::of(b)) {
+                (Kind::Null, Kind::Null) => Ordering::Equal,
+                (Kind::Bool(a), Kind::Bool(b)) => a.cmp(&b),
+                (Kind::Number(a), Kind::Number(b)) => a.cmp(&b),
+                (Kind::String(a), Kind::String(b)) => a.cmp(&b),
+                (Kind::Array(a), Kind::Array(b)) => {
+                    stack.push((Children::Array(a.iter()), Children::Array(b.iter()), a.len().cmp(&b.len())));
+                    Ordering::Equal
+                }
+                (Kind::Object(a), Kind::Object(b)) => {
+                    let lengths = a.len().cmp(&b.len());
+                    stack.push((Children::object(a), Children::object(b), lengths));
+                    Ordering::Equal
+                }
+                (a, b) => a.rank().cmp(&b.rank()),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+
+            // Find the next pair of children, or settle finished containers by length
+            (a, b) = loop {
+                let Some((xs, ys, lengths)) = stack.last_mut() else {
+                    return Ordering::Equal;
+                };
+                match (xs.next(), ys.next()) {
+                    (Some((ka, va)), Some((kb, vb))) => match ka.cmp(&kb) {
+                        Ordering::Equal => break (va, vb),
+                        ordering => return ordering,
+                    },
+                    _ => {
+                        let lengths = *lengths;
+                        stack.pop();
+                        if lengths != Ordering::Equal {
+                            return lengths;
+                        }
+                    }
+                }
+            };
+        }
+    }
+
+    fn semantic_hash<H: Hasher>(&self, state: &mut H) {
+        let mut stack = vec![(None, self)];
+        while let Some((key, value)) = stack.pop() {
+            key.hash(state);
+            let kind = Kind::of(value);
+            kind.rank().hash(state);
+            match kind {
+                Kind::Null => {}
+                Kind::Bool(b) => b.hash(state),
+                Kind::Number(n) => n.hash(state),
+                Kind::String(s) => s.hash(state),
+                Kind::Array(elements) => {
+                    elements.len().hash(state);
+                    stack.extend(elements.iter().rev().map(|element| (None, element)));
+                }
+                Kind::Object(pairs) => {
+                    pairs.len().hash(state);
+                    stack.extend(sorted_entries(pairs).into_iter().rev().map(|(key, value)| (Some(key), value)));
+                }
+            }
+        }
+    }
+}
+
+/// A [`UbjsonValue`] that implements `Eq`, `Hash` and `Ord` by semantic comparison.
+///
+/// Use it to put values in a `HashSet` or `BTreeMap`, or to dedup them.
+#[derive(Debug, Clone)]
+pub struct SemanticValue(pub UbjsonValue);
+
+impl SemanticValue {
+    /// Unwrap the inner value.
+    pub fn into_inner(self) -> UbjsonValue {
+        self.0
+    }
+}
+
+impl From<UbjsonValue> for SemanticValue {
+    fn from(value: UbjsonValue) -> Self {
+        SemanticValue(value)
+    }
+}
+
+impl PartialEq for SemanticValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.semantic_eq(&other.0)
+    }
+}
+
+impl Eq for SemanticValue {}
+
+impl PartialOrd for SemanticValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemanticValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.semantic_cmp(&other.0)
+    }
+}
+
+impl Hash for SemanticValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.semantic_hash(state);
+    }
+}
+
+/// The logical kind of a value, with encoding differences removed.
+enum Kind<'a> {
+    Null,
+    Bool(bool),
+    Number(Number<'a>),
+    String(Cow<'a, str>),
+    Array(&'a [UbjsonValue]),
+    Object(&'a HashMap<String, UbjsonValue>),
+}
+
+impl<'a> Kind<'a> {
+    fn of(value: &'a UbjsonValue) -> Self {
+        match value {
+            UbjsonValue::Null => Kind::Null,
+            UbjsonValue::Bool(b) => Kind::Bool(*b),
+            UbjsonValue::Int8(n) => Kind::Number(Number::Int(*n as i128)),
+            UbjsonValue::UInt8(n) => Kind::Number(Number::Int(*n as i128)),
+            UbjsonValue::Int16(n) => Kind::Number(Number::Int(*n as i128)),
+            UbjsonValue::Int32(n) => Kind::Number(Number::Int(*n as i128)),
+            UbjsonValue::Int64(n) => Kind::Number(Number::Int(*n as i128)),
+            UbjsonValue::Float32(f) => Kind::Number(Number::from_f64(*f as f64)),
+            UbjsonValue::Float64(f) => Kind::Number(Number::from_f64(*f)),
+            UbjsonValue::HighPrecision(s) => Kind::Number(Number::parse(s)),
+            UbjsonValue::Char(c) => Kind::String(Cow::Owned(c.to_string())),
+            UbjsonValue::String(s) => Kind::String(Cow::Borrowed(s)),
+            UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => Kind::Array(elements),
+            UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => Kind::Object(pairs),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Kind::Null => 0,
+            Kind::Bool(_) => 1,
+            Kind::Number(_) => 2,
+            Kind::String(_) => 3,
+            Kind::Array(_) => 4,
+            Kind::Object(_) => 5,
+        }
+    }
+}
+
+/// A number in canonical form. Floats with an integral value that fits in an
+/// `i128` are always stored as `Int`, so equal numbers have equal forms.
+#[derive(Debug, Clone, Copy)]
+enum Number<'a> {
+    Int(i128),
+    Float(f64),
+    NaN,
+    Text(&'a str),
+}
+
+/// 2^127, the first float above `i128::MAX`.
+const I128_LIMIT: f64 = 170141183460469231731687303715884105728.0;
+
+impl<'a> Number<'a> {
+    fn from_f64(f: f64) -> Self {
+        if f.is_nan() {
+            Number::NaN
+        } else if f.fract() == 0.0 && (-I128_LIMIT..I128_LIMIT).contains(&f) {
+            Number::Int(f as i128)
+        } else {
+            Number::Float(f)
+        }
+    }
+
+    fn parse(s: &'a str) -> Self {
+        if let Ok(n) = s.parse::<i128>() {
+            return Number::Int(n);
+        }
+        match s.parse::<f64>() {
+            Ok(f) if f.is_finite() => Number::from_f64(f),
+            _ => Number::Text(s),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Number::Int(_) | Number::Float(_) => 0,
+            Number::NaN => 1,
+            Number::Text(_) => 2,
+        }
+    }
+}
+
+/// Compare an integer with a float that is not integral or is outside the `i128` range.
+fn cmp_int_float(i: i128, f: f64) -> Ordering {
+    if f >= I128_LIMIT {
+        Ordering::Less
+    } else if f < -I128_LIMIT {
+        Ordering::Greater
+    } else if i <= f.floor() as i128 {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+impl PartialEq for Number<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number<'_> {}
+
+impl PartialOrd for Number<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (*self, *other) {
+            (Number::Int(a), Number::Int(b)) => a.cmp(&b),
+            (Number::Int(a), Number::Float(b)) => cmp_int_float(a, b),
+            (Number::Float(a), Number::Int(b)) => cmp_int_float(b, a).reverse(),
+            (Number::Float(a), Number::Float(b)) => a.total_cmp(&b),
+            (Number::Text(a), Number::Text(b)) => a.cmp(b),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+impl Hash for Number<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Number::Int(n) => (0u8, n).hash(state),
+            Number::Float(f) => (1u8, f.to_bits()).hash(state),
+            Number::NaN => 2u8.hash(state),
+            Number::Text(s) => (3u8, s).hash(state),
+        }
+    }
+}
+
+/// The children of a container in comparison order, with their keys for objects.
+enum Children<'a> {
+    Array(std::slice::Iter<'a, UbjsonValue>),
+    Object(std::vec::IntoIter<(&'a String, &'a UbjsonValue)>),
+}
+
+impl<'a> Children<'a> {
+    fn object(pairs: &'a HashMap<String, UbjsonValue>) -> Self {
+        Children::Object(sorted_entries(pairs).into_iter())
+    }
+
+    fn next(&mut self) -> Option<(Option<&'a String>, &'a UbjsonValue)> {
+        match self {
+            Children::Array(elements) => elements.next().map(|element| (None, element)),
+            Children::Object(entries) => entries.next().map(|(key, value)| (Some(key), value)),
+        }
+    }
+}
+
+fn sorted_entries(pairs: &HashMap<String, UbjsonValue>) -> Vec<(&String, &UbjsonValue)> {
+    let mut entries: Vec<_> = pairs.iter().collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    entries
+}
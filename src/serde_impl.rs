@@ -3,11 +3,19 @@
 //! This module provides implementations of serde's Serializer and Deserializer traits
 //! to enable automatic serialization of Rust types using derive macros.
 
+// Generic methods keep the `T: ?Sized` plus `where T: Serialize` form of serde's docs
+#![allow(clippy::multiple_bound_locations)]
+
 #[cfg(feature = "serde")]
 use serde::{ser, de};
-use std::io::{Write, Read};
-use crate::{UbjsonSerializer, UbjsonDeserializer, UbjsonError, UbjsonValue};
-
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use crate::read::{Read, Reference};
+use crate::{UbjsonSerializer, UbjsonDeserializer, UbjsonError, UbjsonType, UbjsonValue};
+
+// Serializing to a writer first builds the whole value as a UbjsonValue tree
+// and then writes it in one go, so the serializer settings apply to all of it
 #[cfg(feature = "serde")]
 impl<W: Write> ser::Serializer for UbjsonSerializer<W> {
     type Ok = ();
@@ -21,79 +29,63 @@ impl<W: Write> ser::Serializer for UbjsonSerializer<W> {
     type SerializeStructVariant = SerializeStructVariant<W>;
 
     fn serialize_bool(mut self, v: bool) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Bool(v))
+        self.serialize_value(&ValueSerializer.serialize_bool(v)?)
     }
 
     fn serialize_i8(mut self, v: i8) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Int8(v))
+        self.serialize_value(&ValueSerializer.serialize_i8(v)?)
     }
 
     fn serialize_i16(mut self, v: i16) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Int16(v))
+        self.serialize_value(&ValueSerializer.serialize_i16(v)?)
     }
 
     fn serialize_i32(mut self, v: i32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Int32(v))
+        self.serialize_value(&ValueSerializer.serialize_i32(v)?)
     }
 
     fn serialize_i64(mut self, v: i64) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Int64(v))
+        self.serialize_value(&ValueSerializer.serialize_i64(v)?)
     }
 
     fn serialize_u8(mut self, v: u8) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::UInt8(v))
+        self.serialize_value(&ValueSerializer.serialize_u8(v)?)
     }
 
     fn serialize_u16(mut self, v: u16) -> Result<Self::Ok, Self::Error> {
-        // UBJSON doesn't have u16, use i32 if it fits, otherwise i64
-        if v <= i32::MAX as u16 {
-            self.serialize_value(&UbjsonValue::Int32(v as i32))
-        } else {
-            self.serialize_value(&UbjsonValue::Int64(v as i64))
-        }
+        self.serialize_value(&ValueSerializer.serialize_u16(v)?)
     }
 
     fn serialize_u32(mut self, v: u32) -> Result<Self::Ok, Self::Error> {
-        // UBJSON doesn't have u32, use i64 to ensure it fits
-        self.serialize_value(&UbjsonValue::Int64(v as i64))
+        self.serialize_value(&ValueSerializer.serialize_u32(v)?)
     }
 
     fn serialize_u64(mut self, v: u64) -> Result<Self::Ok, Self::Error> {
-        // UBJSON doesn't have u64, check if it fits in i64
-        if v <= i64::MAX as u64 {
-            self.serialize_value(&UbjsonValue::Int64(v as i64))
-        } else {
-            // Use high-precision number for values that don't fit in i64
-            self.serialize_value(&UbjsonValue::HighPrecision(v.to_string()))
-        }
+        self.serialize_value(&ValueSerializer.serialize_u64(v)?)
     }
 
     fn serialize_f32(mut self, v: f32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Float32(v))
+        self.serialize_value(&ValueSerializer.serialize_f32(v)?)
     }
 
     fn serialize_f64(mut self, v: f64) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Float64(v))
+        self.serialize_value(&ValueSerializer.serialize_f64(v)?)
     }
 
     fn serialize_char(mut self, v: char) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Char(v))
+        self.serialize_value(&ValueSerializer.serialize_char(v)?)
     }
 
     fn serialize_str(mut self, v: &str) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::String(v.to_string()))
+        self.serialize_value(&ValueSerializer.serialize_str(v)?)
     }
 
     fn serialize_bytes(mut self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        // Serialize bytes as an array of uint8 values
-        let byte_values: Vec<UbjsonValue> = v.iter()
-            .map(|&b| UbjsonValue::UInt8(b))
-            .collect();
-        self.serialize_value(&UbjsonValue::Array(byte_values))
+        self.serialize_value(&ValueSerializer.serialize_bytes(v)?)
     }
 
     fn serialize_none(mut self) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Null)
+        self.serialize_value(&ValueSerializer.serialize_none()?)
     }
 
     fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
@@ -104,53 +96,54 @@ impl<W: Write> ser::Serializer for UbjsonSerializer<W> {
     }
 
     fn serialize_unit(mut self) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Null)
+        self.serialize_value(&ValueSerializer.serialize_unit()?)
     }
 
-    fn serialize_unit_struct(mut self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::Null)
+    fn serialize_unit_struct(mut self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_value(&ValueSerializer.serialize_unit_struct(name)?)
     }
 
     fn serialize_unit_variant(
         mut self,
-        _name: &'static str,
-        _variant_index: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        self.serialize_value(&UbjsonValue::String(variant.to_string()))
+        self.serialize_value(&ValueSerializer.serialize_unit_variant(name, variant_index, variant)?)
     }
 
     fn serialize_newtype_struct<T: ?Sized>(
-        self,
-        _name: &'static str,
+        mut self,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: ser::Serialize,
     {
+        if Exact::from_name(name).is_some() {
+            return self.serialize_value(&ValueSerializer.serialize_newtype_struct(name, value)?);
+        }
         value.serialize(self)
     }
 
     fn serialize_newtype_variant<T: ?Sized>(
         mut self,
-        _name: &'static str,
-        _variant_index: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: ser::Serialize,
     {
-        let ubjson_value = to_ubjson_value(value)?;
-        let mut map = std::collections::HashMap::new();
-        map.insert(variant.to_string(), ubjson_value);
-        self.serialize_value(&UbjsonValue::Object(map))
+        let value = ValueSerializer.serialize_newtype_variant(name, variant_index, variant, value)?;
+        self.serialize_value(&value)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         Ok(SerializeSeq {
             serializer: self,
-            elements: Vec::with_capacity(len.unwrap_or(0)),
+            inner: ValueSerializer.serialize_seq(len)?,
         })
     }
 
@@ -168,23 +161,21 @@ impl<W: Write> ser::Serializer for UbjsonSerializer<W> {
 
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
-        _variant_index: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         Ok(SerializeTupleVariant {
             serializer: self,
-            variant: variant.to_string(),
-            elements: Vec::with_capacity(len),
+            inner: ValueSerializer.serialize_tuple_variant(name, variant_index, variant, len)?,
         })
     }
 
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         Ok(SerializeMap {
             serializer: self,
-            pairs: std::collections::HashMap::new(),
-            current_key: None,
+            inner: ValueSerializer.serialize_map(len)?,
         })
     }
 
@@ -198,24 +189,182 @@ impl<W: Write> ser::Serializer for UbjsonSerializer<W> {
 
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
-        _variant_index: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         Ok(SerializeStructVariant {
             serializer: self,
-            variant: variant.to_string(),
-            pairs: std::collections::HashMap::with_capacity(len),
+            inner: ValueSerializer.serialize_struct_variant(name, variant_index, variant, len)?,
         })
     }
 }
 
+// Serializing through a mutable reference writes to the same writer, so several
+// values can be written in sequence with one serializer
+#[cfg(feature = "serde")]
+impl<'a, W: Write> ser::Serializer for &'a mut UbjsonSerializer<W> {
+    type Ok = ();
+    type Error = UbjsonError;
+    type SerializeSeq = SerializeSeq<&'a mut W>;
+    type SerializeTuple = SerializeSeq<&'a mut W>;
+    type SerializeTupleStruct = SerializeSeq<&'a mut W>;
+    type SerializeTupleVariant = SerializeTupleVariant<&'a mut W>;
+    type SerializeMap = SerializeMap<&'a mut W>;
+    type SerializeStruct = SerializeMap<&'a mut W>;
+    type SerializeStructVariant = SerializeStructVariant<&'a mut W>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_i64(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_u64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_none()
+    }
+
+    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        self.by_ref().serialize_some(value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.by_ref().serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(self, name: &'static str, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        self.by_ref().serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        self.by_ref().serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.by_ref().serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.by_ref().serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.by_ref().serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.by_ref().serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.by_ref().serialize_map(len)
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        self.by_ref().serialize_struct(name, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.by_ref().serialize_struct_variant(name, variant_index, variant, len)
+    }
+}
+
 // Helper struct for serializing sequences
 #[cfg(feature = "serde")]
 pub struct SerializeSeq<W: Write> {
     serializer: UbjsonSerializer<W>,
-    elements: Vec<UbjsonValue>,
+    inner: SerializeValueSeq,
 }
 
 #[cfg(feature = "serde")]
@@ -227,14 +376,12 @@ impl<W: Write> ser::SerializeSeq for SerializeSeq<W> {
     where
         T: ser::Serialize,
     {
-        // Convert the value to UbjsonValue using a helper
-        let ubjson_value = to_ubjson_value(value)?;
-        self.elements.push(ubjson_value);
-        Ok(())
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
     }
 
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        self.serializer.serialize_value(&UbjsonValue::Array(self.elements))
+        let value = ser::SerializeSeq::end(self.inner)?;
+        self.serializer.serialize_value(&value)
     }
 }
 
@@ -276,8 +423,7 @@ impl<W: Write> ser::SerializeTupleStruct for SerializeSeq<W> {
 #[cfg(feature = "serde")]
 pub struct SerializeTupleVariant<W: Write> {
     serializer: UbjsonSerializer<W>,
-    variant: String,
-    elements: Vec<UbjsonValue>,
+    inner: SerializeValueTupleVariant,
 }
 
 #[cfg(feature = "serde")]
@@ -289,15 +435,12 @@ impl<W: Write> ser::SerializeTupleVariant for SerializeTupleVariant<W> {
     where
         T: ser::Serialize,
     {
-        let ubjson_value = to_ubjson_value(value)?;
-        self.elements.push(ubjson_value);
-        Ok(())
+        ser::SerializeTupleVariant::serialize_field(&mut self.inner, value)
     }
 
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        let mut map = std::collections::HashMap::new();
-        map.insert(self.variant, UbjsonValue::Array(self.elements));
-        self.serializer.serialize_value(&UbjsonValue::Object(map))
+        let value = ser::SerializeTupleVariant::end(self.inner)?;
+        self.serializer.serialize_value(&value)
     }
 }
 
@@ -305,8 +448,7 @@ impl<W: Write> ser::SerializeTupleVariant for SerializeTupleVariant<W> {
 #[cfg(feature = "serde")]
 pub struct SerializeMap<W: Write> {
     serializer: UbjsonSerializer<W>,
-    pairs: std::collections::HashMap<String, UbjsonValue>,
-    current_key: Option<String>,
+    inner: SerializeValueMap,
 }
 
 #[cfg(feature = "serde")]
@@ -318,41 +460,19 @@ impl<W: Write> ser::SerializeMap for SerializeMap<W> {
     where
         T: ser::Serialize,
     {
-        let ubjson_value = to_ubjson_value(key)?;
-        
-        // Convert the key to a string
-        let key_string = match ubjson_value {
-            UbjsonValue::String(s) => s,
-            UbjsonValue::Char(c) => c.to_string(),
-            UbjsonValue::Int8(n) => n.to_string(),
-            UbjsonValue::UInt8(n) => n.to_string(),
-            UbjsonValue::Int16(n) => n.to_string(),
-            UbjsonValue::Int32(n) => n.to_string(),
-            UbjsonValue::Int64(n) => n.to_string(),
-            UbjsonValue::Float32(n) => n.to_string(),
-            UbjsonValue::Float64(n) => n.to_string(),
-            UbjsonValue::Bool(b) => b.to_string(),
-            _ => return Err(UbjsonError::serde("Map keys must be convertible to strings")),
-        };
-        
-        self.current_key = Some(key_string);
-        Ok(())
+        ser::SerializeMap::serialize_key(&mut self.inner, key)
     }
 
     fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
     where
         T: ser::Serialize,
     {
-        let key = self.current_key.take()
-            .ok_or_else(|| UbjsonError::serde("serialize_value called without serialize_key"))?;
-        
-        let ubjson_value = to_ubjson_value(value)?;
-        self.pairs.insert(key, ubjson_value);
-        Ok(())
+        ser::SerializeMap::serialize_value(&mut self.inner, value)
     }
 
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        self.serializer.serialize_value(&UbjsonValue::Object(self.pairs))
+        let value = ser::SerializeMap::end(self.inner)?;
+        self.serializer.serialize_value(&value)
     }
 }
 
@@ -382,8 +502,7 @@ impl<W: Write> ser::SerializeStruct for SerializeMap<W> {
 #[cfg(feature = "serde")]
 pub struct SerializeStructVariant<W: Write> {
     serializer: UbjsonSerializer<W>,
-    variant: String,
-    pairs: std::collections::HashMap<String, UbjsonValue>,
+    inner: SerializeValueStructVariant,
 }
 
 #[cfg(feature = "serde")]
@@ -399,249 +518,1790 @@ impl<W: Write> ser::SerializeStructVariant for SerializeStructVariant<W> {
     where
         T: ser::Serialize,
     {
-        let ubjson_value = to_ubjson_value(value)?;
-        self.pairs.insert(key.to_string(), ubjson_value);
-        Ok(())
+        ser::SerializeStructVariant::serialize_field(&mut self.inner, key, value)
     }
 
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        let mut map = std::collections::HashMap::new();
-        map.insert(self.variant, UbjsonValue::Object(self.pairs));
-        self.serializer.serialize_value(&UbjsonValue::Object(map))
+        let value = ser::SerializeStructVariant::end(self.inner)?;
+        self.serializer.serialize_value(&value)
     }
 }
 
-// Helper function to convert any serializable value to UbjsonValue
+/// Serializer that builds a [`UbjsonValue`] tree instead of writing bytes.
+///
+/// This is what [`to_value`](crate::to_value) uses. The tree is the same one
+/// that decoding the serializer's output would produce.
 #[cfg(feature = "serde")]
-fn to_ubjson_value<T: ?Sized>(value: &T) -> Result<UbjsonValue, UbjsonError>
-where
-    T: ser::Serialize,
-{
-    let mut buffer = Vec::new();
-    let serializer = UbjsonSerializer::new(&mut buffer);
-    value.serialize(serializer)?;
-    
-    let mut deserializer = UbjsonDeserializer::new(buffer.as_slice());
-    deserializer.deserialize_value()
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueSerializer;
+
+#[cfg(feature = "serde")]
+impl ser::Serializer for ValueSerializer {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+    type SerializeSeq = SerializeValueSeq;
+    type SerializeTuple = SerializeValueSeq;
+    type SerializeTupleStruct = SerializeValueSeq;
+    type SerializeTupleVariant = SerializeValueTupleVariant;
+    type SerializeMap = SerializeValueMap;
+    type SerializeStruct = SerializeValueMap;
+    type SerializeStructVariant = SerializeValueStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Int8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Int16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Int32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Int64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::UInt8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        // UBJSON doesn't have u16, and every u16 fits in i32
+        Ok(UbjsonValue::Int32(v as i32))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        // UBJSON doesn't have u32, use i64 to ensure it fits
+        Ok(UbjsonValue::Int64(v as i64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        // UBJSON doesn't have u64, check if it fits in i64
+        match i64::try_from(v) {
+            Ok(n) => Ok(UbjsonValue::Int64(n)),
+            // Use high-precision number for values that don't fit in i64
+            Err(_) => Ok(UbjsonValue::HighPrecision(v.to_string())),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Float32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Float64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::from_char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        // Serialize bytes as an array of uint8 values
+        Ok(UbjsonValue::Array(v.iter().map(|&b| UbjsonValue::UInt8(b)).collect()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Null)
+    }
+
+    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        let value = value.serialize(self)?;
+        match Exact::from_name(name) {
+            Some(exact) => exact.restore(value).map_err(|value| UbjsonError::invalid_type(exact.expected(), &value)),
+            None => Ok(value),
+        }
+    }
+
+    fn serialize_newtype_variant<T: ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        let mut map = HashMap::new();
+        map.insert(variant.to_string(), value.serialize(self)?);
+        Ok(UbjsonValue::Object(map))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeValueSeq {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeValueTupleVariant {
+            variant: variant.to_string(),
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeValueMap {
+            pairs: HashMap::new(),
+            current_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeValueStructVariant {
+            variant: variant.to_string(),
+            pairs: HashMap::with_capacity(len),
+        })
+    }
+}
+
+// Helper struct for building arrays
+#[cfg(feature = "serde")]
+pub struct SerializeValueSeq {
+    elements: Vec<UbjsonValue>,
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeSeq for SerializeValueSeq {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        self.elements.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Array(self.elements))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeTuple for SerializeValueSeq {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeTupleStruct for SerializeValueSeq {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+// Helper struct for building tuple variants
+#[cfg(feature = "serde")]
+pub struct SerializeValueTupleVariant {
+    variant: String,
+    elements: Vec<UbjsonValue>,
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeTupleVariant for SerializeValueTupleVariant {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        self.elements.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let mut map = HashMap::new();
+        map.insert(self.variant, UbjsonValue::Array(self.elements));
+        Ok(UbjsonValue::Object(map))
+    }
+}
+
+// Helper struct for building maps
+#[cfg(feature = "serde")]
+pub struct SerializeValueMap {
+    pairs: HashMap<String, UbjsonValue>,
+    current_key: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeMap for SerializeValueMap {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        // Convert the key to a string
+        let key_string = match key.serialize(ValueSerializer)? {
+            UbjsonValue::String(s) => s,
+            UbjsonValue::Char(c) => c.to_string(),
+            UbjsonValue::Int8(n) => n.to_string(),
+            UbjsonValue::UInt8(n) => n.to_string(),
+            UbjsonValue::Int16(n) => n.to_string(),
+            UbjsonValue::Int32(n) => n.to_string(),
+            UbjsonValue::Int64(n) => n.to_string(),
+            UbjsonValue::Float32(n) => n.to_string(),
+            UbjsonValue::Float64(n) => n.to_string(),
+            UbjsonValue::Bool(b) => b.to_string(),
+            _ => return Err(UbjsonError::serde("Map keys must be convertible to strings")),
+        };
+
+        self.current_key = Some(key_string);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        let key = self.current_key.take()
+            .ok_or_else(|| UbjsonError::serde("serialize_value called without serialize_key"))?;
+
+        self.pairs.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(UbjsonValue::Object(self.pairs))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeStruct for SerializeValueMap {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_field<T: ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        ser::SerializeMap::serialize_key(self, key)?;
+        ser::SerializeMap::serialize_value(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+// Helper struct for building struct variants
+#[cfg(feature = "serde")]
+pub struct SerializeValueStructVariant {
+    variant: String,
+    pairs: HashMap<String, UbjsonValue>,
+}
+
+#[cfg(feature = "serde")]
+impl ser::SerializeStructVariant for SerializeValueStructVariant {
+    type Ok = UbjsonValue;
+    type Error = UbjsonError;
+
+    fn serialize_field<T: ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: ser::Serialize,
+    {
+        self.pairs.insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let mut map = HashMap::new();
+        map.insert(self.variant, UbjsonValue::Object(self.pairs));
+        Ok(UbjsonValue::Object(map))
+    }
+}
+
+// Deserializer implementation: each value is decoded in full and then handed
+// to the implementation for `UbjsonValue`
+#[cfg(feature = "serde")]
+impl<'de, R: Read<'de>> de::Deserializer<'de> for &mut UbjsonDeserializer<R> {
+    type Error = UbjsonError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_any(visitor)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_bool(visitor)
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_i8(visitor)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_i16(visitor)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_i32(visitor)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_i128(visitor)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_u8(visitor)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_u16(visitor)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_u32(visitor)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_u128(visitor)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_f32(visitor)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_f64(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_char(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Strings are read directly so that slice input can be borrowed
+        match self.read_str_value()? {
+            Some(Reference::Borrowed(s)) => visitor.visit_borrowed_str(s),
+            Some(Reference::Copied(s)) => visitor.visit_str(s),
+            None => self.deserialize_value()?.deserialize_str(visitor),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_string(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_option(visitor)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_unit(visitor)
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self, name: &'static str, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_map(visitor)
+    }
+
+    fn deserialize_struct<V>(self, name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_identifier(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_value()?.deserialize_ignored_any(visitor)
+    }
+}
+
+// Deserializing by value forwards to the implementation for `&mut UbjsonDeserializer`
+#[cfg(feature = "serde")]
+impl<'de, R: Read<'de>> de::Deserializer<'de> for UbjsonDeserializer<R> {
+    type Error = UbjsonError;
+
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_any(visitor)
+    }
+
+    fn deserialize_bool<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_bool(visitor)
+    }
+
+    fn deserialize_i8<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_i8(visitor)
+    }
+
+    fn deserialize_i16<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_i16(visitor)
+    }
+
+    fn deserialize_i32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_i32(visitor)
+    }
+
+    fn deserialize_i64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_i64(visitor)
+    }
+
+    fn deserialize_i128<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_i128(visitor)
+    }
+
+    fn deserialize_u8<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_u8(visitor)
+    }
+
+    fn deserialize_u16<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_u16(visitor)
+    }
+
+    fn deserialize_u32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_u32(visitor)
+    }
+
+    fn deserialize_u64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_u64(visitor)
+    }
+
+    fn deserialize_u128<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_u128(visitor)
+    }
+
+    fn deserialize_f32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_f32(visitor)
+    }
+
+    fn deserialize_f64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_f64(visitor)
+    }
+
+    fn deserialize_char<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_char(visitor)
+    }
+
+    fn deserialize_str<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_str(visitor)
+    }
+
+    fn deserialize_string<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_string(visitor)
+    }
+
+    fn deserialize_bytes<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_bytes(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_option<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_option(visitor)
+    }
+
+    fn deserialize_unit<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_unit(visitor)
+    }
+
+    fn deserialize_unit_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_seq<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(mut self, name: &'static str, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_map<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_map(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_identifier<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_identifier(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        (&mut self).deserialize_ignored_any(visitor)
+    }
+}
+
+// Conversions shared by the owned and borrowed `UbjsonValue` deserializers
+
+/// Read any integer, or a high-precision number holding an integer, that fits in `T`.
+#[cfg(feature = "serde")]
+fn integer<T: TryFrom<i128>>(value: &UbjsonValue, expected: &'static str) -> Result<T, UbjsonError> {
+    value.as_i128()
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| UbjsonError::invalid_type(expected, value))
+}
+
+/// Read any number as a float.
+#[cfg(feature = "serde")]
+fn float(value: &UbjsonValue, expected: &'static str) -> Result<f64, UbjsonError> {
+    match value {
+        UbjsonValue::Float32(f) => Ok(*f as f64),
+        UbjsonValue::Float64(f) => Ok(*f),
+        UbjsonValue::Int8(n) => Ok(*n as f64),
+        UbjsonValue::UInt8(n) => Ok(*n as f64),
+        UbjsonValue::Int16(n) => Ok(*n as f64),
+        UbjsonValue::Int32(n) => Ok(*n as f64),
+        UbjsonValue::Int64(n) => Ok(*n as f64),
+        UbjsonValue::HighPrecision(s) => s.parse::<f64>()
+            .map_err(|_| UbjsonError::serde(format!("Cannot parse high-precision number as f64: {}", s))),
+        _ => Err(UbjsonError::invalid_type(expected, value)),
+    }
+}
+
+/// Read a char, or a string holding exactly one character.
+#[cfg(feature = "serde")]
+fn character(value: &UbjsonValue) -> Result<char, UbjsonError> {
+    match value {
+        UbjsonValue::Char(c) => Ok(*c),
+        UbjsonValue::String(s) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(UbjsonError::serde("String must contain exactly one character to deserialize as char")),
+            }
+        }
+        _ => Err(UbjsonError::invalid_type("char", value)),
+    }
+}
+
+/// Read an array of uint8 values as bytes.
+#[cfg(feature = "serde")]
+fn byte_buf(value: &UbjsonValue) -> Result<Vec<u8>, UbjsonError> {
+    match value {
+        UbjsonValue::Array(arr) | UbjsonValue::StronglyTypedArray { elements: arr, .. } => {
+            arr.iter()
+                .map(|element| match element {
+                    UbjsonValue::UInt8(b) => Ok(*b),
+                    UbjsonValue::Int8(b) if *b >= 0 => Ok(*b as u8),
+                    _ => Err(UbjsonError::serde("Array elements must be bytes (0-255) to deserialize as bytes")),
+                })
+                .collect()
+        }
+        _ => Err(UbjsonError::invalid_type("array of bytes", value)),
+    }
+}
+
+// UbjsonValue implements Serialize and Deserialize itself. Other formats see its
+// natural shape (numbers, strings, sequences and maps), while this crate's own
+// serializers and deserializers recognise a few private newtype names and keep
+// every variant exactly, including high-precision numbers and typed containers.
+
+/// Newtype name that asks this crate's deserializers for the exact value.
+#[cfg(feature = "serde")]
+const VALUE_TOKEN: &str = "$ubjson::Value";
+#[cfg(feature = "serde")]
+const HIGH_PRECISION_TOKEN: &str = "$ubjson::HighPrecision";
+#[cfg(feature = "serde")]
+const CHAR_TOKEN: &str = "$ubjson::Char";
+
+/// Element type markers that can appear in a typed container header.
+#[cfg(feature = "serde")]
+const TYPED_MARKERS: &[u8; 13] = b"ZTFiUIlLdDHCS";
+
+#[cfg(feature = "serde")]
+macro_rules! typed_tokens {
+    ($prefix:literal) => {
+        [
+            concat!($prefix, "Z"), concat!($prefix, "T"), concat!($prefix, "F"),
+            concat!($prefix, "i"), concat!($prefix, "U"), concat!($prefix, "I"),
+            concat!($prefix, "l"), concat!($prefix, "L"), concat!($prefix, "d"),
+            concat!($prefix, "D"), concat!($prefix, "H"), concat!($prefix, "C"),
+            concat!($prefix, "S"),
+        ]
+    };
+}
+
+/// Typed container names, indexed by `[is_object][is_uncounted][marker]`.
+#[cfg(feature = "serde")]
+const TYPED_TOKENS: [[[&str; 13]; 2]; 2] = [
+    [typed_tokens!("$ubjson::TypedArray::"), typed_tokens!("$ubjson::UncountedTypedArray::")],
+    [typed_tokens!("$ubjson::TypedObject::"), typed_tokens!("$ubjson::UncountedTypedObject::")],
+];
+
+#[cfg(feature = "serde")]
+fn typed_token(object: bool, element_type: UbjsonType, counted: bool) -> Option<&'static str> {
+    let index = TYPED_MARKERS.iter().position(|&marker| marker == element_type.to_byte())?;
+    Some(TYPED_TOKENS[object as usize][!counted as usize][index])
+}
+
+/// A variant whose natural serde shape would be read back as a different one.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy)]
+enum Exact {
+    HighPrecision,
+    Char,
+    TypedArray { element_type: UbjsonType, counted: bool },
+    TypedObject { value_type: UbjsonType, counted: bool },
+}
+
+#[cfg(feature = "serde")]
+impl Exact {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            HIGH_PRECISION_TOKEN => return Some(Exact::HighPrecision),
+            CHAR_TOKEN => return Some(Exact::Char),
+            _ => {}
+        }
+        let (kind, marker) = name.strip_prefix("$ubjson::")?.split_once("::")?;
+        let element_type = match marker.as_bytes() {
+            [byte] if TYPED_MARKERS.contains(byte) => UbjsonType::from_byte(*byte).ok()?,
+            _ => return None,
+        };
+        match kind {
+            "TypedArray" => Some(Exact::TypedArray { element_type, counted: true }),
+            "UncountedTypedArray" => Some(Exact::TypedArray { element_type, counted: false }),
+            "TypedObject" => Some(Exact::TypedObject { value_type: element_type, counted: true }),
+            "UncountedTypedObject" => Some(Exact::TypedObject { value_type: element_type, counted: false }),
+            _ => None,
+        }
+    }
+
+    /// Rebuild the exact variant from its natural shape, or hand the value back.
+    fn restore(self, value: UbjsonValue) -> Result<UbjsonValue, UbjsonValue> {
+        match (self, value) {
+            (Exact::HighPrecision, UbjsonValue::String(s)) => Ok(UbjsonValue::HighPrecision(s)),
+            (Exact::Char, UbjsonValue::Char(c)) => Ok(UbjsonValue::Char(c)),
+            (Exact::Char, UbjsonValue::String(s)) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(UbjsonValue::Char(c)),
+                    _ => Err(UbjsonValue::String(s)),
+                }
+            }
+            (Exact::TypedArray { element_type, counted }, UbjsonValue::Array(elements)) => {
+                Ok(UbjsonValue::StronglyTypedArray {
+                    element_type,
+                    count: counted.then_some(elements.len()),
+                    elements,
+                })
+            }
+            (Exact::TypedObject { value_type, counted }, UbjsonValue::Object(pairs)) => {
+                Ok(UbjsonValue::StronglyTypedObject {
+                    value_type,
+                    count: counted.then_some(pairs.len()),
+                    pairs,
+                })
+            }
+            (_, value) => Err(value),
+        }
+    }
+
+    fn expected(self) -> &'static str {
+        match self {
+            Exact::HighPrecision => "high-precision number",
+            Exact::Char => "char",
+            Exact::TypedArray { .. } => "array",
+            Exact::TypedObject { .. } => "object",
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ser::Serialize for UbjsonValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self {
+            UbjsonValue::Null => serializer.serialize_unit(),
+            UbjsonValue::Bool(b) => serializer.serialize_bool(*b),
+            UbjsonValue::Int8(n) => serializer.serialize_i8(*n),
+            UbjsonValue::UInt8(n) => serializer.serialize_u8(*n),
+            UbjsonValue::Int16(n) => serializer.serialize_i16(*n),
+            UbjsonValue::Int32(n) => serializer.serialize_i32(*n),
+            UbjsonValue::Int64(n) => serializer.serialize_i64(*n),
+            UbjsonValue::Float32(f) => serializer.serialize_f32(*f),
+            UbjsonValue::Float64(f) => serializer.serialize_f64(*f),
+            UbjsonValue::HighPrecision(s) => serializer.serialize_newtype_struct(HIGH_PRECISION_TOKEN, s),
+            // An ASCII char already comes back as a Char
+            UbjsonValue::Char(c) if c.is_ascii() => serializer.serialize_char(*c),
+            UbjsonValue::Char(c) => serializer.serialize_newtype_struct(CHAR_TOKEN, c),
+            UbjsonValue::String(s) => serializer.serialize_str(s),
+            UbjsonValue::Array(elements) => serializer.collect_seq(elements),
+            UbjsonValue::Object(pairs) => serializer.collect_map(pairs),
+            UbjsonValue::StronglyTypedArray { element_type, count, elements } => {
+                match typed_token(false, *element_type, count.is_some()) {
+                    Some(token) => serializer.serialize_newtype_struct(token, elements),
+                    None => serializer.collect_seq(elements),
+                }
+            }
+            UbjsonValue::StronglyTypedObject { value_type, count, pairs } => {
+                match typed_token(true, *value_type, count.is_some()) {
+                    Some(token) => serializer.serialize_newtype_struct(token, pairs),
+                    None => serializer.collect_map(pairs),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> de::Deserialize<'de> for UbjsonValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, ValueVisitor)
+    }
+}
+
+/// Hand a value to a visitor so that [`ValueVisitor`] can rebuild it exactly.
+#[cfg(feature = "serde")]
+fn deserialize_exact<'de, V>(value: UbjsonValue, visitor: V) -> Result<V::Value, UbjsonError>
+where
+    V: de::Visitor<'de>,
+{
+    let (token, inner) = match value {
+        UbjsonValue::HighPrecision(s) => (HIGH_PRECISION_TOKEN, UbjsonValue::String(s)),
+        UbjsonValue::StronglyTypedArray { element_type, count, elements } => {
+            match typed_token(false, element_type, count.is_some()) {
+                Some(token) => (token, UbjsonValue::Array(elements)),
+                None => return visitor.visit_seq(SeqDeserializer::new(elements)),
+            }
+        }
+        UbjsonValue::StronglyTypedObject { value_type, count, pairs } => {
+            match typed_token(true, value_type, count.is_some()) {
+                Some(token) => (token, UbjsonValue::Object(pairs)),
+                None => return visitor.visit_map(MapDeserializer::new(pairs)),
+            }
+        }
+        // Skip the single-key enum shortcut in deserialize_any
+        UbjsonValue::Object(pairs) => return visitor.visit_map(MapDeserializer::new(pairs)),
+        other => return de::Deserializer::deserialize_any(other, visitor),
+    };
+    let mut entry = HashMap::with_capacity(1);
+    entry.insert(token.to_string(), inner);
+    visitor.visit_map(MapDeserializer::new(entry))
+}
+
+#[cfg(feature = "serde")]
+struct ValueVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> de::Visitor<'de> for ValueVisitor {
+    type Value = UbjsonValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any UBJSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Bool(v))
+    }
+
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Int8(v))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Int16(v))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Int32(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Int64(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match i64::try_from(v) {
+            Ok(n) => Ok(UbjsonValue::Int64(n)),
+            Err(_) => Ok(UbjsonValue::HighPrecision(v.to_string())),
+        }
+    }
+
+    fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::UInt8(v))
+    }
+
+    fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Int32(v as i32))
+    }
+
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Int64(v as i64))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_u128(v as u128)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match i64::try_from(v) {
+            Ok(n) => Ok(UbjsonValue::Int64(n)),
+            Err(_) => Ok(UbjsonValue::HighPrecision(v.to_string())),
+        }
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Float32(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Float64(v))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Array(v.iter().map(|&b| UbjsonValue::UInt8(b)).collect()))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(UbjsonValue::Null)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(UbjsonValue::Array(elements))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut pairs = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry::<String, UbjsonValue>()? {
+            pairs.insert(key, value);
+        }
+        if pairs.len() == 1
+            && let Some(exact) = pairs.keys().next().and_then(|key| Exact::from_name(key))
+        {
+            let (key, value) = pairs.drain().next().unwrap();
+            return match exact.restore(value) {
+                Ok(value) => Ok(value),
+                Err(value) => {
+                    pairs.insert(key, value);
+                    Ok(UbjsonValue::Object(pairs))
+                }
+            };
+        }
+        Ok(UbjsonValue::Object(pairs))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        use de::VariantAccess;
+
+        let (variant, access): (String, _) = data.variant()?;
+        let mut pairs = HashMap::with_capacity(1);
+        pairs.insert(variant, access.newtype_variant()?);
+        Ok(UbjsonValue::Object(pairs))
+    }
+}
+
+// A UbjsonValue can be deserialized directly, which is what `from_value` uses
+#[cfg(feature = "serde")]
+impl<'de> de::Deserializer<'de> for UbjsonValue {
+    type Error = UbjsonError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::Null => visitor.visit_unit(),
+            UbjsonValue::Bool(b) => visitor.visit_bool(b),
+            UbjsonValue::Int8(n) => visitor.visit_i8(n),
+            UbjsonValue::UInt8(n) => visitor.visit_u8(n),
+            UbjsonValue::Int16(n) => visitor.visit_i16(n),
+            UbjsonValue::Int32(n) => visitor.visit_i32(n),
+            UbjsonValue::Int64(n) => visitor.visit_i64(n),
+            UbjsonValue::Float32(f) => visitor.visit_f32(f),
+            UbjsonValue::Float64(f) => visitor.visit_f64(f),
+            UbjsonValue::HighPrecision(s) => visitor.visit_string(s),
+            UbjsonValue::Char(c) => visitor.visit_char(c),
+            UbjsonValue::String(s) => visitor.visit_string(s),
+            UbjsonValue::Array(arr) => visitor.visit_seq(SeqDeserializer::new(arr)),
+            UbjsonValue::Object(mut obj) => {
+                // Check if this could be an enum (object with exactly one key-value pair)
+                if obj.len() == 1 {
+                    let (variant, value) = obj.drain().next().unwrap();
+                    visitor.visit_enum(EnumDeserializer::new(variant, value))
+                } else {
+                    visitor.visit_map(MapDeserializer::new(obj))
+                }
+            }
+            UbjsonValue::StronglyTypedArray { elements, .. } => visitor.visit_seq(SeqDeserializer::new(elements)),
+            UbjsonValue::StronglyTypedObject { pairs, .. } => visitor.visit_map(MapDeserializer::new(pairs)),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::Bool(b) => visitor.visit_bool(b),
+            _ => Err(UbjsonError::invalid_type("bool", &self)),
+        }
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i8(integer(&self, "i8")?)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i16(integer(&self, "i16")?)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i32(integer(&self, "i32")?)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i64(integer(&self, "i64")?)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i128(integer(&self, "i128")?)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u8(integer(&self, "u8")?)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u16(integer(&self, "u16")?)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u32(integer(&self, "u32")?)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u64(integer(&self, "u64")?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u128(integer(&self, "u128")?)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f32(float(&self, "f32")? as f32)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(float(&self, "f64")?)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_char(character(&self)?)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::String(s) => visitor.visit_string(s),
+            UbjsonValue::Char(c) => visitor.visit_string(c.to_string()),
+            _ => Err(UbjsonError::invalid_type("string", &self)),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_byte_buf(byte_buf(&self)?)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::Null => visitor.visit_unit(),
+            _ => Err(UbjsonError::invalid_type("null for unit", &self)),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if name == VALUE_TOKEN {
+            return deserialize_exact(self, visitor);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::Array(arr) |
+            UbjsonValue::StronglyTypedArray { elements: arr, .. } => {
+                visitor.visit_seq(SeqDeserializer::new(arr))
+            }
+            _ => Err(UbjsonError::invalid_type("array", &self)),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::Object(obj) |
+            UbjsonValue::StronglyTypedObject { pairs: obj, .. } => {
+                visitor.visit_map(MapDeserializer::new(obj))
+            }
+            _ => Err(UbjsonError::invalid_type("object", &self)),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::String(variant) => {
+                // Unit variant
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            UbjsonValue::Object(mut obj) => {
+                if obj.len() == 1 {
+                    let (variant, value) = obj.drain().next().unwrap();
+                    visitor.visit_enum(EnumDeserializer::new(variant, value))
+                } else {
+                    Err(UbjsonError::serde("Enum object must have exactly one key-value pair"))
+                }
+            }
+            _ => Err(UbjsonError::invalid_type("string or object for enum", &self)),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        drop(self);
+        visitor.visit_unit()
+    }
 }
 
-// Deserializer implementation
+// Deserializing from a reference borrows strings and keys from the tree
 #[cfg(feature = "serde")]
-impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
+impl<'de> de::Deserializer<'de> for &'de UbjsonValue {
     type Error = UbjsonError;
 
-    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        
-        // Special handling for potential enums: if it's an object with exactly one key-value pair,
-        // it might be an enum variant, so try to deserialize it as an enum first
-        match value {
-            UbjsonValue::Object(mut obj) if obj.len() == 1 => {
-                // This could be an enum variant, try enum deserialization
-                let (variant, variant_value) = obj.drain().next().unwrap();
-                visitor.visit_enum(EnumDeserializer::new(variant, variant_value))
+        match self {
+            UbjsonValue::Null => visitor.visit_unit(),
+            UbjsonValue::Bool(b) => visitor.visit_bool(*b),
+            UbjsonValue::Int8(n) => visitor.visit_i8(*n),
+            UbjsonValue::UInt8(n) => visitor.visit_u8(*n),
+            UbjsonValue::Int16(n) => visitor.visit_i16(*n),
+            UbjsonValue::Int32(n) => visitor.visit_i32(*n),
+            UbjsonValue::Int64(n) => visitor.visit_i64(*n),
+            UbjsonValue::Float32(f) => visitor.visit_f32(*f),
+            UbjsonValue::Float64(f) => visitor.visit_f64(*f),
+            UbjsonValue::HighPrecision(s) => visitor.visit_borrowed_str(s),
+            UbjsonValue::Char(c) => visitor.visit_char(*c),
+            UbjsonValue::String(s) => visitor.visit_borrowed_str(s),
+            UbjsonValue::Array(arr) => visitor.visit_seq(SeqRefDeserializer::new(arr)),
+            UbjsonValue::Object(obj) => {
+                // Check if this could be an enum (object with exactly one key-value pair)
+                match single_entry(obj) {
+                    Some((variant, value)) => visitor.visit_enum(EnumRefDeserializer { variant, value }),
+                    None => visitor.visit_map(MapRefDeserializer::new(obj)),
+                }
             }
-            _ => self.deserialize_ubjson_value(value, visitor)
+            UbjsonValue::StronglyTypedArray { elements, .. } => visitor.visit_seq(SeqRefDeserializer::new(elements)),
+            UbjsonValue::StronglyTypedObject { pairs, .. } => visitor.visit_map(MapRefDeserializer::new(pairs)),
         }
     }
 
-    fn deserialize_bool<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Bool(b) => visitor.visit_bool(b),
-            _ => Err(UbjsonError::serde(format!("Expected bool, found {}", value.type_name()))),
+        match self {
+            UbjsonValue::Bool(b) => visitor.visit_bool(*b),
+            _ => Err(UbjsonError::invalid_type("bool", self)),
         }
     }
 
-    fn deserialize_i8<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Int8(n) => visitor.visit_i8(n),
-            UbjsonValue::UInt8(n) if n <= i8::MAX as u8 => visitor.visit_i8(n as i8),
-            _ => Err(UbjsonError::serde(format!("Expected i8, found {}", value.type_name()))),
-        }
+        visitor.visit_i8(integer(self, "i8")?)
     }
 
-    fn deserialize_i16<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Int8(n) => visitor.visit_i16(n as i16),
-            UbjsonValue::UInt8(n) => visitor.visit_i16(n as i16),
-            UbjsonValue::Int16(n) => visitor.visit_i16(n),
-            _ => Err(UbjsonError::serde(format!("Expected i16, found {}", value.type_name()))),
-        }
+        visitor.visit_i16(integer(self, "i16")?)
     }
 
-    fn deserialize_i32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Int8(n) => visitor.visit_i32(n as i32),
-            UbjsonValue::UInt8(n) => visitor.visit_i32(n as i32),
-            UbjsonValue::Int16(n) => visitor.visit_i32(n as i32),
-            UbjsonValue::Int32(n) => visitor.visit_i32(n),
-            _ => Err(UbjsonError::serde(format!("Expected i32, found {}", value.type_name()))),
-        }
+        visitor.visit_i32(integer(self, "i32")?)
     }
 
-    fn deserialize_i64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Int8(n) => visitor.visit_i64(n as i64),
-            UbjsonValue::UInt8(n) => visitor.visit_i64(n as i64),
-            UbjsonValue::Int16(n) => visitor.visit_i64(n as i64),
-            UbjsonValue::Int32(n) => visitor.visit_i64(n as i64),
-            UbjsonValue::Int64(n) => visitor.visit_i64(n),
-            _ => Err(UbjsonError::serde(format!("Expected i64, found {}", value.type_name()))),
-        }
+        visitor.visit_i64(integer(self, "i64")?)
     }
 
-    fn deserialize_u8<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::UInt8(n) => visitor.visit_u8(n),
-            UbjsonValue::Int8(n) if n >= 0 => visitor.visit_u8(n as u8),
-            _ => Err(UbjsonError::serde(format!("Expected u8, found {}", value.type_name()))),
-        }
+        visitor.visit_i128(integer(self, "i128")?)
     }
 
-    fn deserialize_u16<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::UInt8(n) => visitor.visit_u16(n as u16),
-            UbjsonValue::Int8(n) if n >= 0 => visitor.visit_u16(n as u16),
-            UbjsonValue::Int16(n) if n >= 0 => visitor.visit_u16(n as u16),
-            UbjsonValue::Int32(n) if n >= 0 && n <= u16::MAX as i32 => visitor.visit_u16(n as u16),
-            _ => Err(UbjsonError::serde(format!("Expected u16, found {}", value.type_name()))),
-        }
+        visitor.visit_u8(integer(self, "u8")?)
     }
 
-    fn deserialize_u32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::UInt8(n) => visitor.visit_u32(n as u32),
-            UbjsonValue::Int8(n) if n >= 0 => visitor.visit_u32(n as u32),
-            UbjsonValue::Int16(n) if n >= 0 => visitor.visit_u32(n as u32),
-            UbjsonValue::Int32(n) if n >= 0 => visitor.visit_u32(n as u32),
-            UbjsonValue::Int64(n) if n >= 0 && n <= u32::MAX as i64 => visitor.visit_u32(n as u32),
-            _ => Err(UbjsonError::serde(format!("Expected u32, found {}", value.type_name()))),
-        }
+        visitor.visit_u16(integer(self, "u16")?)
     }
 
-    fn deserialize_u64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::UInt8(n) => visitor.visit_u64(n as u64),
-            UbjsonValue::Int8(n) if n >= 0 => visitor.visit_u64(n as u64),
-            UbjsonValue::Int16(n) if n >= 0 => visitor.visit_u64(n as u64),
-            UbjsonValue::Int32(n) if n >= 0 => visitor.visit_u64(n as u64),
-            UbjsonValue::Int64(n) if n >= 0 => visitor.visit_u64(n as u64),
-            UbjsonValue::HighPrecision(s) => {
-                // Try to parse high-precision number as u64
-                s.parse::<u64>()
-                    .map_err(|_| UbjsonError::serde(format!("Cannot parse high-precision number as u64: {}", s)))
-                    .and_then(|n| visitor.visit_u64(n))
-            }
-            _ => Err(UbjsonError::serde(format!("Expected u64, found {}", value.type_name()))),
-        }
+        visitor.visit_u32(integer(self, "u32")?)
     }
 
-    fn deserialize_f32<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Float32(f) => visitor.visit_f32(f),
-            UbjsonValue::Float64(f) => visitor.visit_f32(f as f32),
-            UbjsonValue::Int8(n) => visitor.visit_f32(n as f32),
-            UbjsonValue::UInt8(n) => visitor.visit_f32(n as f32),
-            UbjsonValue::Int16(n) => visitor.visit_f32(n as f32),
-            UbjsonValue::Int32(n) => visitor.visit_f32(n as f32),
-            UbjsonValue::Int64(n) => visitor.visit_f32(n as f32),
-            _ => Err(UbjsonError::serde(format!("Expected f32, found {}", value.type_name()))),
-        }
+        visitor.visit_u64(integer(self, "u64")?)
     }
 
-    fn deserialize_f64<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Float32(f) => visitor.visit_f64(f as f64),
-            UbjsonValue::Float64(f) => visitor.visit_f64(f),
-            UbjsonValue::Int8(n) => visitor.visit_f64(n as f64),
-            UbjsonValue::UInt8(n) => visitor.visit_f64(n as f64),
-            UbjsonValue::Int16(n) => visitor.visit_f64(n as f64),
-            UbjsonValue::Int32(n) => visitor.visit_f64(n as f64),
-            UbjsonValue::Int64(n) => visitor.visit_f64(n as f64),
-            UbjsonValue::HighPrecision(s) => {
-                // Try to parse high-precision number as f64
-                s.parse::<f64>()
-                    .map_err(|_| UbjsonError::serde(format!("Cannot parse high-precision number as f64: {}", s)))
-                    .and_then(|f| visitor.visit_f64(f))
-            }
-            _ => Err(UbjsonError::serde(format!("Expected f64, found {}", value.type_name()))),
-        }
+        visitor.visit_u128(integer(self, "u128")?)
     }
 
-    fn deserialize_char<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Char(c) => visitor.visit_char(c),
-            UbjsonValue::String(s) => {
-                let mut chars = s.chars();
-                match (chars.next(), chars.next()) {
-                    (Some(c), None) => visitor.visit_char(c),
-                    _ => Err(UbjsonError::serde("String must contain exactly one character to deserialize as char")),
-                }
-            }
-            _ => Err(UbjsonError::serde(format!("Expected char, found {}", value.type_name()))),
-        }
+        visitor.visit_f32(float(self, "f32")? as f32)
     }
 
-    fn deserialize_str<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::String(s) => visitor.visit_string(s),
+        visitor.visit_f64(float(self, "f64")?)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_char(character(self)?)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            UbjsonValue::String(s) => visitor.visit_borrowed_str(s),
             UbjsonValue::Char(c) => visitor.visit_string(c.to_string()),
-            _ => Err(UbjsonError::serde(format!("Expected string, found {}", value.type_name()))),
+            _ => Err(UbjsonError::invalid_type("string", self)),
         }
     }
 
@@ -652,26 +2312,11 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
         self.deserialize_str(visitor)
     }
 
-    fn deserialize_bytes<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Array(arr) => {
-                // Convert array of UInt8 values to bytes
-                let mut bytes = Vec::with_capacity(arr.len());
-                for element in arr {
-                    match element {
-                        UbjsonValue::UInt8(b) => bytes.push(b),
-                        UbjsonValue::Int8(b) if b >= 0 => bytes.push(b as u8),
-                        _ => return Err(UbjsonError::serde("Array elements must be bytes (0-255) to deserialize as bytes")),
-                    }
-                }
-                visitor.visit_byte_buf(bytes)
-            }
-            _ => Err(UbjsonError::serde(format!("Expected array of bytes, found {}", value.type_name()))),
-        }
+        visitor.visit_byte_buf(byte_buf(self)?)
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -681,25 +2326,23 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
         self.deserialize_bytes(visitor)
     }
 
-    fn deserialize_option<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
+        match self {
             UbjsonValue::Null => visitor.visit_none(),
-            _ => visitor.visit_some(UbjsonValueDeserializer::new(value)),
+            _ => visitor.visit_some(self),
         }
     }
 
-    fn deserialize_unit<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
+        match self {
             UbjsonValue::Null => visitor.visit_unit(),
-            _ => Err(UbjsonError::serde(format!("Expected null for unit, found {}", value.type_name()))),
+            _ => Err(UbjsonError::invalid_type("null for unit", self)),
         }
     }
 
@@ -716,26 +2359,29 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
+        if name == VALUE_TOKEN {
+            // The result owns its data anyway, so nothing is lost by cloning
+            return deserialize_exact(self.clone(), visitor);
+        }
         visitor.visit_newtype_struct(self)
     }
 
-    fn deserialize_seq<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Array(arr) | 
+        match self {
+            UbjsonValue::Array(arr) |
             UbjsonValue::StronglyTypedArray { elements: arr, .. } => {
-                visitor.visit_seq(SeqDeserializer::new(arr))
+                visitor.visit_seq(SeqRefDeserializer::new(arr))
             }
-            _ => Err(UbjsonError::serde(format!("Expected array, found {}", value.type_name()))),
+            _ => Err(UbjsonError::invalid_type("array", self)),
         }
     }
 
@@ -758,17 +2404,16 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
         self.deserialize_seq(visitor)
     }
 
-    fn deserialize_map<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
-            UbjsonValue::Object(obj) | 
+        match self {
+            UbjsonValue::Object(obj) |
             UbjsonValue::StronglyTypedObject { pairs: obj, .. } => {
-                visitor.visit_map(MapDeserializer::new(obj))
+                visitor.visit_map(MapRefDeserializer::new(obj))
             }
-            _ => Err(UbjsonError::serde(format!("Expected object, found {}", value.type_name()))),
+            _ => Err(UbjsonError::invalid_type("object", self)),
         }
     }
 
@@ -785,7 +2430,7 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
     }
 
     fn deserialize_enum<V>(
-        mut self,
+        self,
         _name: &'static str,
         _variants: &'static [&'static str],
         visitor: V,
@@ -793,21 +2438,16 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
     where
         V: de::Visitor<'de>,
     {
-        let value = self.deserialize_value()?;
-        match value {
+        match self {
             UbjsonValue::String(variant) => {
                 // Unit variant
-                visitor.visit_enum(variant.into_deserializer())
-            }
-            UbjsonValue::Object(mut obj) => {
-                if obj.len() == 1 {
-                    let (variant, value) = obj.drain().next().unwrap();
-                    visitor.visit_enum(EnumDeserializer::new(variant, value))
-                } else {
-                    Err(UbjsonError::serde("Enum object must have exactly one key-value pair"))
-                }
+                visitor.visit_enum(de::value::BorrowedStrDeserializer::<UbjsonError>::new(variant))
             }
-            _ => Err(UbjsonError::serde(format!("Expected string or object for enum, found {}", value.type_name()))),
+            UbjsonValue::Object(obj) => match single_entry(obj) {
+                Some((variant, value)) => visitor.visit_enum(EnumRefDeserializer { variant, value }),
+                None => Err(UbjsonError::serde("Enum object must have exactly one key-value pair")),
+            },
+            _ => Err(UbjsonError::invalid_type("string or object for enum", self)),
         }
     }
 
@@ -822,119 +2462,69 @@ impl<'de, R: Read> de::Deserializer<'de> for UbjsonDeserializer<R> {
     where
         V: de::Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        visitor.visit_unit()
     }
 }
 
+/// Get the only entry of an object with exactly one key.
 #[cfg(feature = "serde")]
-impl<R: Read> UbjsonDeserializer<R> {
-    fn deserialize_ubjson_value<'de, V>(&self, value: UbjsonValue, visitor: V) -> Result<V::Value, UbjsonError>
-    where
-        V: de::Visitor<'de>,
-    {
-        match value {
-            UbjsonValue::Null => visitor.visit_unit(),
-            UbjsonValue::Bool(b) => visitor.visit_bool(b),
-            UbjsonValue::Int8(n) => visitor.visit_i8(n),
-            UbjsonValue::UInt8(n) => visitor.visit_u8(n),
-            UbjsonValue::Int16(n) => visitor.visit_i16(n),
-            UbjsonValue::Int32(n) => visitor.visit_i32(n),
-            UbjsonValue::Int64(n) => visitor.visit_i64(n),
-            UbjsonValue::Float32(f) => visitor.visit_f32(f),
-            UbjsonValue::Float64(f) => visitor.visit_f64(f),
-            UbjsonValue::HighPrecision(s) => visitor.visit_string(s),
-            UbjsonValue::Char(c) => visitor.visit_char(c),
-            UbjsonValue::String(s) => visitor.visit_string(s),
-            UbjsonValue::Array(arr) => visitor.visit_seq(SeqDeserializer::new(arr)),
-            UbjsonValue::Object(obj) => visitor.visit_map(MapDeserializer::new(obj)),
-            UbjsonValue::StronglyTypedArray { elements, .. } => visitor.visit_seq(SeqDeserializer::new(elements)),
-            UbjsonValue::StronglyTypedObject { pairs, .. } => visitor.visit_map(MapDeserializer::new(pairs)),
-        }
+fn single_entry(obj: &HashMap<String, UbjsonValue>) -> Option<(&str, &UbjsonValue)> {
+    if obj.len() == 1 {
+        obj.iter().next().map(|(key, value)| (key.as_str(), value))
+    } else {
+        None
     }
 }
 
-// Helper deserializers for complex types
 #[cfg(feature = "serde")]
-struct UbjsonValueDeserializer {
-    value: UbjsonValue,
+struct SeqDeserializer {
+    elements: std::vec::IntoIter<UbjsonValue>,
 }
 
 #[cfg(feature = "serde")]
-impl UbjsonValueDeserializer {
-    fn new(value: UbjsonValue) -> Self {
-        Self { value }
+impl SeqDeserializer {
+    fn new(elements: Vec<UbjsonValue>) -> Self {
+        Self {
+            elements: elements.into_iter(),
+        }
     }
 }
 
 #[cfg(feature = "serde")]
-impl<'de> de::Deserializer<'de> for UbjsonValueDeserializer {
+impl<'de> de::SeqAccess<'de> for SeqDeserializer {
     type Error = UbjsonError;
 
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        match self.value {
-            UbjsonValue::Null => visitor.visit_unit(),
-            UbjsonValue::Bool(b) => visitor.visit_bool(b),
-            UbjsonValue::Int8(n) => visitor.visit_i8(n),
-            UbjsonValue::UInt8(n) => visitor.visit_u8(n),
-            UbjsonValue::Int16(n) => visitor.visit_i16(n),
-            UbjsonValue::Int32(n) => visitor.visit_i32(n),
-            UbjsonValue::Int64(n) => visitor.visit_i64(n),
-            UbjsonValue::Float32(f) => visitor.visit_f32(f),
-            UbjsonValue::Float64(f) => visitor.visit_f64(f),
-            UbjsonValue::HighPrecision(s) => visitor.visit_string(s),
-            UbjsonValue::Char(c) => visitor.visit_char(c),
-            UbjsonValue::String(s) => visitor.visit_string(s),
-            UbjsonValue::Array(arr) => visitor.visit_seq(SeqDeserializer::new(arr)),
-            UbjsonValue::Object(mut obj) => {
-                // Check if this could be an enum (object with exactly one key-value pair)
-                if obj.len() == 1 {
-                    let (variant, value) = obj.drain().next().unwrap();
-                    visitor.visit_enum(EnumDeserializer::new(variant, value))
-                } else {
-                    visitor.visit_map(MapDeserializer::new(obj))
-                }
-            }
-            UbjsonValue::StronglyTypedArray { elements, .. } => visitor.visit_seq(SeqDeserializer::new(elements)),
-            UbjsonValue::StronglyTypedObject { pairs, .. } => visitor.visit_map(MapDeserializer::new(pairs)),
-        }
-    }
-
-    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
     where
-        V: de::Visitor<'de>,
+        T: de::DeserializeSeed<'de>,
     {
-        match self.value {
-            UbjsonValue::Null => visitor.visit_none(),
-            _ => visitor.visit_some(self),
+        match self.elements.next() {
+            Some(value) => seed.deserialize(value).map(Some),
+            None => Ok(None),
         }
     }
 
-    serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
     }
 }
 
 #[cfg(feature = "serde")]
-struct SeqDeserializer {
-    elements: std::vec::IntoIter<UbjsonValue>,
+struct SeqRefDeserializer<'de> {
+    elements: std::slice::Iter<'de, UbjsonValue>,
 }
 
 #[cfg(feature = "serde")]
-impl SeqDeserializer {
-    fn new(elements: Vec<UbjsonValue>) -> Self {
+impl<'de> SeqRefDeserializer<'de> {
+    fn new(elements: &'de [UbjsonValue]) -> Self {
         Self {
-            elements: elements.into_iter(),
+            elements: elements.iter(),
         }
     }
 }
 
 #[cfg(feature = "serde")]
-impl<'de> de::SeqAccess<'de> for SeqDeserializer {
+impl<'de> de::SeqAccess<'de> for SeqRefDeserializer<'de> {
     type Error = UbjsonError;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
@@ -942,10 +2532,14 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer {
         T: de::DeserializeSeed<'de>,
     {
         match self.elements.next() {
-            Some(value) => seed.deserialize(UbjsonValueDeserializer::new(value)).map(Some),
+            Some(value) => seed.deserialize(value).map(Some),
             None => Ok(None),
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -956,7 +2550,7 @@ struct MapDeserializer {
 
 #[cfg(feature = "serde")]
 impl MapDeserializer {
-    fn new(map: std::collections::HashMap<String, UbjsonValue>) -> Self {
+    fn new(map: HashMap<String, UbjsonValue>) -> Self {
         Self {
             entries: map.into_iter(),
             current_value: None,
@@ -986,10 +2580,62 @@ impl<'de> de::MapAccess<'de> for MapDeserializer {
         V: de::DeserializeSeed<'de>,
     {
         match self.current_value.take() {
-            Some(value) => seed.deserialize(UbjsonValueDeserializer::new(value)),
+            Some(value) => seed.deserialize(value),
+            None => Err(UbjsonError::serde("next_value_seed called without next_key_seed")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+#[cfg(feature = "serde")]
+struct MapRefDeserializer<'de> {
+    entries: std::collections::hash_map::Iter<'de, String, UbjsonValue>,
+    current_value: Option<&'de UbjsonValue>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> MapRefDeserializer<'de> {
+    fn new(map: &'de HashMap<String, UbjsonValue>) -> Self {
+        Self {
+            entries: map.iter(),
+            current_value: None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> de::MapAccess<'de> for MapRefDeserializer<'de> {
+    type Error = UbjsonError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.current_value = Some(value);
+                seed.deserialize(de::value::BorrowedStrDeserializer::<UbjsonError>::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.current_value.take() {
+            Some(value) => seed.deserialize(value),
             None => Err(UbjsonError::serde("next_value_seed called without next_key_seed")),
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -1008,25 +2654,30 @@ impl EnumDeserializer {
 #[cfg(feature = "serde")]
 impl<'de> de::EnumAccess<'de> for EnumDeserializer {
     type Error = UbjsonError;
-    type Variant = UbjsonValueDeserializer;
+    type Variant = VariantDeserializer;
 
     fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
     where
         V: de::DeserializeSeed<'de>,
     {
         let variant = seed.deserialize(self.variant.into_deserializer())?;
-        Ok((variant, UbjsonValueDeserializer::new(self.value)))
+        Ok((variant, VariantDeserializer { value: self.value }))
     }
 }
 
 #[cfg(feature = "serde")]
-impl<'de> de::VariantAccess<'de> for UbjsonValueDeserializer {
+struct VariantDeserializer {
+    value: UbjsonValue,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
     type Error = UbjsonError;
 
     fn unit_variant(self) -> Result<(), Self::Error> {
         match self.value {
             UbjsonValue::Null => Ok(()),
-            _ => Err(UbjsonError::serde("Expected null for unit variant")),
+            other => Err(UbjsonError::invalid_type("null for unit variant", &other)),
         }
     }
 
@@ -1034,7 +2685,7 @@ impl<'de> de::VariantAccess<'de> for UbjsonValueDeserializer {
     where
         T: de::DeserializeSeed<'de>,
     {
-        seed.deserialize(self)
+        seed.deserialize(self.value)
     }
 
     fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
@@ -1043,7 +2694,7 @@ impl<'de> de::VariantAccess<'de> for UbjsonValueDeserializer {
     {
         match self.value {
             UbjsonValue::Array(arr) => visitor.visit_seq(SeqDeserializer::new(arr)),
-            _ => Err(UbjsonError::serde("Expected array for tuple variant")),
+            other => Err(UbjsonError::invalid_type("array for tuple variant", &other)),
         }
     }
 
@@ -1057,7 +2708,75 @@ impl<'de> de::VariantAccess<'de> for UbjsonValueDeserializer {
     {
         match self.value {
             UbjsonValue::Object(obj) => visitor.visit_map(MapDeserializer::new(obj)),
-            _ => Err(UbjsonError::serde("Expected object for struct variant")),
+            other => Err(UbjsonError::invalid_type("object for struct variant", &other)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct EnumRefDeserializer<'de> {
+    variant: &'de str,
+    value: &'de UbjsonValue,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> de::EnumAccess<'de> for EnumRefDeserializer<'de> {
+    type Error = UbjsonError;
+    type Variant = VariantRefDeserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(de::value::BorrowedStrDeserializer::<UbjsonError>::new(self.variant))?;
+        Ok((variant, VariantRefDeserializer { value: self.value }))
+    }
+}
+
+#[cfg(feature = "serde")]
+struct VariantRefDeserializer<'de> {
+    value: &'de UbjsonValue,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> de::VariantAccess<'de> for VariantRefDeserializer<'de> {
+    type Error = UbjsonError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            UbjsonValue::Null => Ok(()),
+            other => Err(UbjsonError::invalid_type("null for unit variant", other)),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.value)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            UbjsonValue::Array(arr) => visitor.visit_seq(SeqRefDeserializer::new(arr)),
+            other => Err(UbjsonError::invalid_type("array for tuple variant", other)),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            UbjsonValue::Object(obj) => visitor.visit_map(MapRefDeserializer::new(obj)),
+            other => Err(UbjsonError::invalid_type("object for struct variant", other)),
         }
     }
 }
@@ -1073,4 +2792,4 @@ impl StringDeserializer for String {
     fn into_deserializer(self) -> de::value::StringDeserializer<UbjsonError> {
         de::value::StringDeserializer::new(self)
     }
-}
\ No newline at end of file
+}
@@ -6,6 +6,7 @@
 use std::io::Write;
 use crate::error::{UbjsonError, Result};
 use crate::types::UbjsonType;
+use crate::validate::check_encodable;
 use crate::value::UbjsonValue;
 use crate::encoding::{
     write_type_marker, write_int8, write_uint8, write_int16, write_int32, write_int64,
@@ -13,12 +14,37 @@ use crate::encoding::{
 };
 use crate::types::optimization::{TYPE_MARKER, COUNT_MARKER};
 
+/// A writer that discards its input and counts the bytes written to it.
+#[derive(Debug, Default)]
+pub(crate) struct ByteCounter {
+    count: usize,
+}
+
+impl ByteCounter {
+    /// The number of bytes written so far.
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Serializer for converting values to UBJSON binary format.
 pub struct UbjsonSerializer<W: Write> {
     writer: W,
     optimize_containers: bool,
     current_depth: usize,
     max_depth: usize,
+    strict: bool,
 }
 
 impl<W: Write> UbjsonSerializer<W> {
@@ -32,6 +58,7 @@ impl<W: Write> UbjsonSerializer<W> {
             optimize_containers: false,
             current_depth: 0,
             max_depth: Self::DEFAULT_MAX_DEPTH,
+            strict: false,
         }
     }
 
@@ -42,6 +69,7 @@ impl<W: Write> UbjsonSerializer<W> {
             optimize_containers: optimize,
             current_depth: 0,
             max_depth: Self::DEFAULT_MAX_DEPTH,
+            strict: false,
         }
     }
 
@@ -52,6 +80,7 @@ impl<W: Write> UbjsonSerializer<W> {
             optimize_containers: false,
             current_depth: 0,
             max_depth,
+            strict: false,
         }
     }
 
@@ -62,11 +91,41 @@ impl<W: Write> UbjsonSerializer<W> {
             optimize_containers: optimize,
             current_depth: 0,
             max_depth,
+            strict: false,
         }
     }
 
+    /// Create a serializer with the same settings that writes through a
+    /// mutable reference to this serializer's writer.
+    #[cfg(feature = "serde")]
+    pub(crate) fn by_ref(&mut self) -> UbjsonSerializer<&mut W> {
+        UbjsonSerializer {
+            writer: &mut self.writer,
+            optimize_containers: self.optimize_containers,
+            current_depth: self.current_depth,
+            max_depth: self.max_depth,
+            strict: self.strict,
+        }
+    }
+
+    /// Refuse to write encodings that the UBJSON specification does not allow.
+    pub(crate) fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     /// Serialize a UbjsonValue to the writer.
+    ///
+    /// Strongly-typed containers are checked before anything is written, so
+    /// a value that breaks their invariants (see [`UbjsonValue::validate`])
+    /// fails without producing partial output.
     pub fn serialize_value(&mut self, value: &UbjsonValue) -> Result<()> {
+        check_encodable(value, self.strict)?;
+        self.write_value(value)
+    }
+
+    /// Write a value that has already been checked.
+    fn write_value(&mut self, value: &UbjsonValue) -> Result<()> {
         match value {
             UbjsonValue::Null => self.serialize_null(),
             UbjsonValue::Bool(b) => self.serialize_bool(*b),
@@ -157,11 +216,46 @@ impl<W: Write> UbjsonSerializer<W> {
     }
 
     /// Serialize a character.
+    ///
+    /// A UBJSON char is a single byte, so anything outside ASCII is written as
+    /// a one-character string instead.
     fn serialize_char(&mut self, value: char) -> Result<()> {
+        if !value.is_ascii() {
+            let mut buffer = [0u8; 4];
+            return self.serialize_string(value.encode_utf8(&mut buffer));
+        }
         write_type_marker(&mut self.writer, UbjsonType::Char)?;
         write_char(&mut self.writer, value)
     }
 
+    /// Check that a character fits the single-byte char encoding of a typed container.
+    fn check_typed_char(value: char) -> Result<()> {
+        if !value.is_ascii() {
+            return Err(UbjsonError::InvalidChar(format!(
+                "'{}' cannot be written as a single-byte char",
+                value
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the header of a strongly-typed container before writing anything.
+    fn check_typed_header(&self, element_type: UbjsonType, count: Option<usize>, container: &str) -> Result<()> {
+        if !element_type.is_primitive() {
+            return Err(UbjsonError::invalid_format(format!(
+                "Strongly-typed {} can only contain primitive types, found: {}",
+                container, element_type
+            )));
+        }
+        if self.strict && count.is_none() {
+            return Err(UbjsonError::NonConformant(format!(
+                "strongly-typed {} must declare a count",
+                container
+            )));
+        }
+        Ok(())
+    }
+
     /// Serialize a string.
     fn serialize_string(&mut self, value: &str) -> Result<()> {
         write_type_marker(&mut self.writer, UbjsonType::String)?;
@@ -169,6 +263,7 @@ impl<W: Write> UbjsonSerializer<W> {
     }
 
     /// Serialize a standard array.
+    #[allow(clippy::collapsible_if)]
     fn serialize_array(&mut self, array: &[UbjsonValue]) -> Result<()> {
         // Check depth limit
         if self.current_depth >= self.max_depth {
@@ -190,7 +285,7 @@ impl<W: Write> UbjsonSerializer<W> {
         
         // Serialize each element
         for element in array {
-            self.serialize_value(element)?;
+            self.write_value(element)?;
         }
         
         // Decrease depth
@@ -201,6 +296,7 @@ impl<W: Write> UbjsonSerializer<W> {
     }
 
     /// Serialize a standard object.
+    #[allow(clippy::collapsible_if)]
     fn serialize_object(&mut self, object: &std::collections::HashMap<String, UbjsonValue>) -> Result<()> {
         // Check depth limit
         if self.current_depth >= self.max_depth {
@@ -225,7 +321,7 @@ impl<W: Write> UbjsonSerializer<W> {
             // Write the key as a string without type marker (compact string format)
             write_string(&mut self.writer, key)?;
             // Write the value
-            self.serialize_value(value)?;
+            self.write_value(value)?;
         }
         
         // Decrease depth
@@ -269,6 +365,9 @@ impl<W: Write> UbjsonSerializer<W> {
                 return None;
             }
         }
+        if !array.iter().all(fits_typed_container) {
+            return None;
+        }
 
         Some(first_type)
     }
@@ -293,11 +392,15 @@ impl<W: Write> UbjsonSerializer<W> {
                 return None;
             }
         }
+        if !object.values().all(fits_typed_container) {
+            return None;
+        }
 
         Some(first_type)
     }
 
     /// Serialize a strongly-typed array with optimization markers.
+    #[allow(clippy::redundant_pattern_matching)]
     fn serialize_strongly_typed_array(
         &mut self,
         element_type: UbjsonType,
@@ -308,6 +411,11 @@ impl<W: Write> UbjsonSerializer<W> {
         if self.current_depth >= self.max_depth {
             return Err(UbjsonError::DepthLimitExceeded(self.max_depth));
         }
+        self.check_typed_header(element_type, count, "arrays")?;
+        if !self.strict && !elements.iter().all(fits_typed_container) {
+            // Non-ASCII chars need a string marker, which a typed array can't hold
+            return self.serialize_array(elements);
+        }
 
         // Write array start marker
         write_type_marker(&mut self.writer, UbjsonType::ArrayStart)?;
@@ -344,6 +452,7 @@ impl<W: Write> UbjsonSerializer<W> {
     }
 
     /// Serialize a strongly-typed object with optimization markers.
+    #[allow(clippy::redundant_pattern_matching)]
     fn serialize_strongly_typed_object(
         &mut self,
         value_type: UbjsonType,
@@ -354,6 +463,11 @@ impl<W: Write> UbjsonSerializer<W> {
         if self.current_depth >= self.max_depth {
             return Err(UbjsonError::DepthLimitExceeded(self.max_depth));
         }
+        self.check_typed_header(value_type, count, "objects")?;
+        if !self.strict && !pairs.values().all(fits_typed_container) {
+            // Non-ASCII chars need a string marker, which a typed object can't hold
+            return self.serialize_object(pairs);
+        }
 
         // Write object start marker
         write_type_marker(&mut self.writer, UbjsonType::ObjectStart)?;
@@ -396,11 +510,10 @@ impl<W: Write> UbjsonSerializer<W> {
     fn serialize_value_without_type_marker(&mut self, value: &UbjsonValue, expected_type: UbjsonType) -> Result<()> {
         // Verify the value matches the expected type
         if value.get_type() != expected_type {
-            return Err(UbjsonError::invalid_format(format!(
-                "Value type {} does not match expected type {}",
-                value.get_type(),
-                expected_type
-            )));
+            return Err(UbjsonError::TypedContainerMismatch {
+                expected: expected_type,
+                found: value.get_type(),
+            });
         }
 
         match value {
@@ -415,7 +528,10 @@ impl<W: Write> UbjsonSerializer<W> {
             UbjsonValue::Float32(n) => write_float32(&mut self.writer, *n),
             UbjsonValue::Float64(n) => write_float64(&mut self.writer, *n),
             UbjsonValue::HighPrecision(s) => write_string(&mut self.writer, s),
-            UbjsonValue::Char(c) => write_char(&mut self.writer, *c),
+            UbjsonValue::Char(c) => {
+                Self::check_typed_char(*c)?;
+                write_char(&mut self.writer, *c)
+            }
             UbjsonValue::String(s) => write_string(&mut self.writer, s),
             // Containers should not be in optimized containers (only primitives)
             _ => Err(UbjsonError::invalid_format(
@@ -425,6 +541,11 @@ impl<W: Write> UbjsonSerializer<W> {
     }
 }
 
+/// Check whether a value can be written without a type marker in a typed container.
+fn fits_typed_container(value: &UbjsonValue) -> bool {
+    !matches!(value, UbjsonValue::Char(c) if !c.is_ascii())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -521,6 +642,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_serialize_float32() {
         let mut buffer = Vec::new();
         let mut serializer = UbjsonSerializer::new(&mut buffer);
@@ -536,6 +658,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_serialize_float64() {
         let mut buffer = Vec::new();
         let mut serializer = UbjsonSerializer::new(&mut buffer);
@@ -581,8 +704,8 @@ mod tests {
         
         serializer.serialize_value(&UbjsonValue::Char('π')).unwrap();
         
-        // π in UTF-8 is [0xCF, 0x80]
-        assert_eq!(buffer, vec![b'C', 0xCF, 0x80]);
+        // π does not fit a single-byte char, so it is written as the string [0xCF, 0x80]
+        assert_eq!(buffer, vec![b'S', b'U', 2, 0xCF, 0x80]);
     }
 
     #[test]
@@ -962,6 +1085,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_serialize_strongly_typed_array_explicit() {
         let mut buffer = Vec::new();
         let mut serializer = UbjsonSerializer::new(&mut buffer);
@@ -1150,11 +1274,13 @@ mod tests {
         let result = serializer.serialize_value(&array);
         assert!(result.is_err());
         
-        if let Err(UbjsonError::InvalidFormat(msg)) = result {
-            assert!(msg.contains("does not match expected type"));
-        } else {
-            panic!("Expected InvalidFormat error");
-        }
+        assert!(matches!(
+            result,
+            Err(UbjsonError::TypedContainerMismatch {
+                expected: UbjsonType::Int8,
+                found: UbjsonType::Int32,
+            })
+        ));
     }
 
 
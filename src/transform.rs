@@ -0,0 +1,228 @@
+//! Whole-tree transformations of [`UbjsonValue`].
+//!
+//! Every traversal here uses an explicit stack instead of recursion, so
+//! documents nested deeper than the call stack allows are handled.
+
+use std::collections::HashMap;
+use crate::path::PathSegment;
+use crate::types::UbjsonType;
+use crate::value::UbjsonValue;
+
+/// Which encoding details [`UbjsonValue::normalize`] removes.
+///
+/// All options are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    flatten_typed_containers: bool,
+    widen_integers: bool,
+    high_precision_to_float: bool,
+}
+
+impl NormalizeOptions {
+    /// Create options that change nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn strongly-typed arrays and objects into plain ones.
+    pub fn with_flatten_typed_containers(mut self, enabled: bool) -> Self {
+        self.flatten_typed_containers = enabled;
+        self
+    }
+
+    /// Turn every integer that fits into `Int64`.
+    pub fn with_widen_integers(mut self, enabled: bool) -> Self {
+        self.widen_integers = enabled;
+        self
+    }
+
+    /// Turn high-precision numbers that parse to a finite `f64` into `Float64`.
+    pub fn with_high_precision_to_float(mut self, enabled: bool) -> Self {
+        self.high_precision_to_float = enabled;
+        self
+    }
+
+    /// The element type a typed container keeps after normalizing its
+    /// elements, or `None` if it has to become a plain container.
+    fn element_type<'a>(
+        &self,
+        element_type: UbjsonType,
+        mut elements: impl Iterator<Item = &'a UbjsonValue>,
+    ) -> Option<UbjsonType> {
+        if self.flatten_typed_containers {
+            None
+        } else if self.widen_integers && element_type.is_integer() {
+            Some(UbjsonType::Int64)
+        } else if self.high_precision_to_float && element_type == UbjsonType::HighPrecision {
+            elements.all(|e| e.as_f64().is_some()).then_some(UbjsonType::Float64)
+        } else {
+            Some(element_type)
+        }
+    }
+
+    fn apply(&self, value: &mut UbjsonValue) {
+        match value {
+            UbjsonValue::Int8(_) | UbjsonValue::UInt8(_) | UbjsonValue::Int16(_) | UbjsonValue::Int32(_)
+                if self.widen_integers =>
+            {
+                *value = UbjsonValue::Int64(value.as_i64().unwrap_or_default());
+            }
+            UbjsonValue::HighPrecision(_) if self.high_precision_to_float => {
+                if let Some(f) = value.as_f64() {
+                    *value = UbjsonValue::Float64(f);
+                }
+            }
+            UbjsonValue::StronglyTypedArray { element_type, elements, .. } => {
+                match self.element_type(*element_type, elements.iter()) {
+                    Some(new_type) => *element_type = new_type,
+                    None => *value = UbjsonValue::Array(std::mem::take(elements)),
+                }
+            }
+            UbjsonValue::StronglyTypedObject { value_type, pairs, .. } => {
+                match self.element_type(*value_type, pairs.values()) {
+                    Some(new_type) => *value_type = new_type,
+                    None => *value = UbjsonValue::Object(std::mem::take(pairs)),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl UbjsonValue {
+    /// Remove the encoding details selected by `options` from this value and
+    /// everything inside it.
+    ///
+    /// Typed containers whose elements change type are retyped to match, or
+    /// flattened when their elements no longer share one type.
+    pub fn normalize(&mut self, options: NormalizeOptions) {
+        self.map_values(|mut value| {
+            options.apply(&mut value);
+            value
+        });
+    }
+
+    /// Replace every value in the tree with the result of `f`.
+    ///
+    /// `f` sees a container before its children, and is then applied to the
+    /// children of the container it returned.
+    pub fn map_values<F>(&mut self, mut f: F)
+    where
+        F: FnMut(UbjsonValue) -> UbjsonValue,
+    {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            *value = f(std::mem::replace(value, UbjsonValue::Null));
+            stack.extend(children_mut(value));
+        }
+    }
+
+    /// Remove every array element and object entry, at any depth, for which
+    /// `f` returns false.
+    ///
+    /// `f` receives the path of each element as it was before any removal.
+    /// Removed values are not visited further.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[PathSegment], &UbjsonValue) -> bool,
+    {
+        let mut path = Vec::new();
+        let mut stack = vec![(0, None, self)];
+        while let Some((depth, segment, value)) = stack.pop() {
+            path.truncate(depth);
+            path.extend(segment);
+            let child_depth = path.len();
+
+            let mut indices = Vec::new();
+            match value {
+                UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+                    let mut kept = Vec::with_capacity(elements.len());
+                    for (index, element) in std::mem::take(elements).into_iter().enumerate() {
+                        path.push(PathSegment::Index(index));
+                        if f(&path, &element) {
+                            kept.push(element);
+                            indices.push(index);
+                        }
+                        path.pop();
+                    }
+                    *elements = kept;
+                }
+                UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => {
+                    pairs.retain(|key, value| {
+                        path.push(PathSegment::Key(key.clone()));
+                        let keep = f(&path, value);
+                        path.pop();
+                        keep
+                    });
+                }
+                _ => {}
+            }
+
+            match value {
+                UbjsonValue::StronglyTypedArray { count: Some(count), elements, .. } => *count = elements.len(),
+                UbjsonValue::StronglyTypedObject { count: Some(count), pairs, .. } => *count = pairs.len(),
+                _ => {}
+            }
+
+            match value {
+                UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+                    for (index, element) in indices.into_iter().zip(elements.iter_mut()).rev() {
+                        stack.push((child_depth, Some(PathSegment::Index(index)), element));
+                    }
+                }
+                UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => {
+                    for (key, value) in sorted_entries_mut(pairs).into_iter().rev() {
+                        stack.push((child_depth, Some(PathSegment::Key(key.clone())), value));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Visit every value in the tree, depth first, with its path from this value.
+    ///
+    /// The root is visited first with an empty path. Array elements are visited
+    /// in order and object entries in key order.
+    pub fn walk<F>(&self, mut f: F)
+    where
+        F: FnMut(&[PathSegment], &UbjsonValue),
+    {
+        let mut path = Vec::new();
+        let mut stack = vec![(0, None, self)];
+        while let Some((depth, segment, value)) = stack.pop() {
+            path.truncate(depth);
+            path.extend(segment);
+            f(&path, value);
+
+            let child_depth = path.len();
+            if let Some(elements) = value.as_array() {
+                for (index, element) in elements.iter().enumerate().rev() {
+                    stack.push((child_depth, Some(PathSegment::Index(index)), element));
+                }
+            } else if let Some(pairs) = value.as_object() {
+                let mut entries: Vec<_> = pairs.iter().collect();
+                entries.sort_unstable_by(|a, b| b.0.cmp(a.0));
+                for (key, value) in entries {
+                    stack.push((child_depth, Some(PathSegment::Key(key.clone())), value));
+                }
+            }
+        }
+    }
+}
+
+fn children_mut(value: &mut UbjsonValue) -> Vec<&mut UbjsonValue> {
+    match value {
+        UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => {
+            elements.iter_mut().collect()
+        }
+        UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => pairs.values_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn sorted_entries_mut(pairs: &mut HashMap<String, UbjsonValue>) -> Vec<(&String, &mut UbjsonValue)> {
+    let mut entries: Vec<_> = pairs.iter_mut().collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    entries
+}
@@ -110,6 +110,7 @@ impl UbjsonType {
     }
 
     /// Check if this type represents a numeric value.
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_numeric(self) -> bool {
         match self {
             UbjsonType::Int8
@@ -125,6 +126,7 @@ impl UbjsonType {
     }
 
     /// Check if this type represents an integer value.
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_integer(self) -> bool {
         match self {
             UbjsonType::Int8
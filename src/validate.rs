@@ -0,0 +1,196 @@
+//! Invariant checks for strongly-typed containers.
+//!
+//! The `StronglyTypedArray` and `StronglyTypedObject` variants can be built
+//! with any contents, but only some of them can be encoded: the element type
+//! must be primitive, every element must have that type and a declared count
+//! must match the number of elements. A typed container holding a non-ASCII
+//! char is still valid: the serializer writes it untyped, with the char as a
+//! string, and only strict mode rejects it.
+
+use std::collections::HashMap;
+use std::fmt;
+use crate::error::{Result, UbjsonError};
+use crate::path::{format_path, PathSegment};
+use crate::types::UbjsonType;
+use crate::value::UbjsonValue;
+
+/// A broken strongly-typed container invariant, found by [`UbjsonValue::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Location of the offending container or element.
+    pub path: Vec<PathSegment>,
+    /// What is wrong there.
+    pub kind: ViolationKind,
+}
+
+/// The kinds of [`Violation`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// A typed container declares a non-primitive element type.
+    NonPrimitiveType(UbjsonType),
+    /// A typed container declares a count other than its number of elements.
+    CountMismatch { count: usize, actual: usize },
+    /// An element of a typed container does not have the container's type.
+    TypeMismatch { expected: UbjsonType, found: UbjsonType },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", format_path(&self.path))?;
+        match &self.kind {
+            ViolationKind::NonPrimitiveType(element_type) => {
+                write!(f, "element type {} is not a primitive type", element_type)
+            }
+            ViolationKind::CountMismatch { count, actual } => {
+                write!(f, "count {} does not match {} elements", count, actual)
+            }
+            ViolationKind::TypeMismatch { expected, found } => {
+                write!(f, "value type {} does not match element type {}", found, expected)
+            }
+        }
+    }
+}
+
+/// Convert to the error the serializer reports for the same problem.
+impl From<ViolationKind> for UbjsonError {
+    fn from(kind: ViolationKind) -> Self {
+        match kind {
+            ViolationKind::NonPrimitiveType(element_type) => UbjsonError::invalid_format(format!(
+                "Strongly-typed containers can only contain primitive types, found: {}",
+                element_type
+            )),
+            ViolationKind::CountMismatch { count, actual } => {
+                UbjsonError::LengthMismatch { expected: count, actual }
+            }
+            ViolationKind::TypeMismatch { expected, found } => {
+                UbjsonError::TypedContainerMismatch { expected, found }
+            }
+        }
+    }
+}
+
+impl UbjsonValue {
+    /// Create a strongly-typed array, checking that every element has `element_type`.
+    pub fn try_strongly_typed_array(element_type: UbjsonType, elements: Vec<UbjsonValue>) -> Result<Self> {
+        let unlabeled = elements.iter().map(|element| ((), element));
+        check_container(element_type, None, elements.len(), unlabeled, fail)?;
+        Ok(UbjsonValue::strongly_typed_array(element_type, elements))
+    }
+
+    /// Create a strongly-typed object, checking that every value has `value_type`.
+    pub fn try_strongly_typed_object(value_type: UbjsonType, pairs: HashMap<String, UbjsonValue>) -> Result<Self> {
+        let unlabeled = pairs.values().map(|value| ((), value));
+        check_container(value_type, None, pairs.len(), unlabeled, fail)?;
+        Ok(UbjsonValue::strongly_typed_object(value_type, pairs))
+    }
+
+    /// Check every strongly-typed container in this value, returning all
+    /// violations in document order if there are any.
+    pub fn validate(&self) -> std::result::Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.walk(|path, value| {
+            let report = |segment: Option<PathSegment>, kind| -> std::result::Result<(), ()> {
+                let mut path = path.to_vec();
+                path.extend(segment);
+                violations.push(Violation { path, kind });
+                Ok(())
+            };
+            let _ = match value {
+                UbjsonValue::StronglyTypedArray { element_type, count, elements } => {
+                    let labeled = elements.iter().enumerate().map(|(index, element)| (PathSegment::Index(index), element));
+                    check_container(*element_type, *count, elements.len(), labeled, report)
+                }
+                UbjsonValue::StronglyTypedObject { value_type, count, pairs } => {
+                    let mut entries: Vec<_> = pairs.iter().collect();
+                    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                    let labeled = entries.into_iter().map(|(key, value)| (PathSegment::Key(key.clone()), value));
+                    check_container(*value_type, *count, pairs.len(), labeled, report)
+                }
+                _ => Ok(()),
+            };
+        });
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+}
+
+/// Check every strongly-typed container in `value`, failing on the first
+/// violation. With `strict`, typed containers must also declare a count and
+/// are written as they are, so their chars must be ASCII.
+pub(crate) fn check_encodable(value: &UbjsonValue, strict: bool) -> Result<()> {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        let (count, container) = match value {
+            UbjsonValue::Array(elements) => {
+                stack.extend(elements);
+                continue;
+            }
+            UbjsonValue::Object(pairs) => {
+                stack.extend(pairs.values());
+                continue;
+            }
+            UbjsonValue::StronglyTypedArray { element_type, count, elements } => {
+                let unlabeled = elements.iter().map(|element| ((), element));
+                check_container(*element_type, *count, elements.len(), unlabeled, fail)?;
+                if strict {
+                    check_ascii_chars(elements.iter())?;
+                }
+                (count, "arrays")
+            }
+            UbjsonValue::StronglyTypedObject { value_type, count, pairs } => {
+                let unlabeled = pairs.values().map(|value| ((), value));
+                check_container(*value_type, *count, pairs.len(), unlabeled, fail)?;
+                if strict {
+                    check_ascii_chars(pairs.values())?;
+                }
+                (count, "objects")
+            }
+            _ => continue,
+        };
+        if strict && count.is_none() {
+            return Err(UbjsonError::NonConformant(format!(
+                "strongly-typed {} must declare a count",
+                container
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn fail(_: Option<()>, kind: ViolationKind) -> Result<()> {
+    Err(kind.into())
+}
+
+fn check_ascii_chars<'a>(mut elements: impl Iterator<Item = &'a UbjsonValue>) -> Result<()> {
+    match elements.find_map(|element| element.as_char().filter(|c| !c.is_ascii())) {
+        Some(c) => Err(UbjsonError::InvalidChar(format!("'{}' cannot be written as a single-byte char", c))),
+        None => Ok(()),
+    }
+}
+
+/// Check one typed container. Each violation is passed to `report` with the
+/// label of the offending element, or `None` for the container itself, and
+/// checking stops if `report` fails.
+fn check_container<'a, S, E>(
+    element_type: UbjsonType,
+    count: Option<usize>,
+    len: usize,
+    elements: impl Iterator<Item = (S, &'a UbjsonValue)>,
+    mut report: impl FnMut(Option<S>, ViolationKind) -> std::result::Result<(), E>,
+) -> std::result::Result<(), E> {
+    if !element_type.is_primitive() {
+        // Every element would mismatch too, so only the type is reported
+        return report(None, ViolationKind::NonPrimitiveType(element_type));
+    }
+    if let Some(count) = count
+        && count != len
+    {
+        report(None, ViolationKind::CountMismatch { count, actual: len })?;
+    }
+    for (segment, element) in elements {
+        let found = element.get_type();
+        if found != element_type {
+            report(Some(segment), ViolationKind::TypeMismatch { expected: element_type, found })?;
+        }
+    }
+    Ok(())
+}
@@ -1,9 +1,14 @@
 //! UBJSON value representation and manipulation.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use crate::error::UbjsonError;
 use crate::types::UbjsonType;
 
 /// Represents any UBJSON value including optimized containers.
+///
+/// With the `serde` feature it implements `Serialize` and `Deserialize`, so it can
+/// be embedded in derived types. Other formats see plain numbers, strings, arrays
+/// and maps; this crate's own serializers keep every variant exactly.
 #[derive(Debug, Clone, PartialEq)]
 pub enum UbjsonValue {
     /// Null value
@@ -27,6 +32,9 @@ pub enum UbjsonValue {
     /// High-precision number as string
     HighPrecision(String),
     /// Single character
+    ///
+    /// UBJSON chars are one byte, so only ASCII characters are written as a
+    /// char; any other character is written as a one-character string.
     Char(char),
     /// UTF-8 string
     String(String),
@@ -89,6 +97,7 @@ impl UbjsonValue {
     }
 
     /// Check if this value is a number (integer, float, or high-precision).
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_number(&self) -> bool {
         match self {
             UbjsonValue::Int8(_)
@@ -104,6 +113,7 @@ impl UbjsonValue {
     }
 
     /// Check if this value is an integer.
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_integer(&self) -> bool {
         match self {
             UbjsonValue::Int8(_)
@@ -131,6 +141,7 @@ impl UbjsonValue {
     }
 
     /// Check if this value is an array (standard or strongly-typed).
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_array(&self) -> bool {
         match self {
             UbjsonValue::Array(_) | UbjsonValue::StronglyTypedArray { .. } => true,
@@ -139,6 +150,7 @@ impl UbjsonValue {
     }
 
     /// Check if this value is an object (standard or strongly-typed).
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_object(&self) -> bool {
         match self {
             UbjsonValue::Object(_) | UbjsonValue::StronglyTypedObject { .. } => true,
@@ -158,10 +170,123 @@ impl UbjsonValue {
     }
 
     /// Check if a container is empty, or false for non-containers.
+    #[allow(clippy::unnecessary_map_or)]
     pub fn is_empty(&self) -> bool {
         self.len().map_or(false, |len| len == 0)
     }
 
+    /// Get the value of a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            UbjsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Get any integer as an `i128`, including a high-precision number holding one.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            UbjsonValue::Int8(n) => Some(*n as i128),
+            UbjsonValue::UInt8(n) => Some(*n as i128),
+            UbjsonValue::Int16(n) => Some(*n as i128),
+            UbjsonValue::Int32(n) => Some(*n as i128),
+            UbjsonValue::Int64(n) => Some(*n as i128),
+            UbjsonValue::HighPrecision(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Get any integer that fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|n| i64::try_from(n).ok())
+    }
+
+    /// Get any non-negative integer that fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_i128().and_then(|n| u64::try_from(n).ok())
+    }
+
+    /// Get any number as an `f64`.
+    ///
+    /// Integers above 2^53 lose precision. A high-precision number must parse
+    /// to a finite `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            UbjsonValue::Float32(f) => Some(*f as f64),
+            UbjsonValue::Float64(f) => Some(*f),
+            UbjsonValue::HighPrecision(s) => s.parse::<f64>().ok().filter(|f| f.is_finite()),
+            _ => self.as_i64().map(|n| n as f64),
+        }
+    }
+
+    /// Get the value of a char.
+    pub fn as_char(&self) -> Option<char> {
+        match self {
+            UbjsonValue::Char(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    /// Get the contents of a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            UbjsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the elements of an array (standard or strongly-typed).
+    pub fn as_array(&self) -> Option<&Vec<UbjsonValue>> {
+        match self {
+            UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Get mutable access to the elements of an array (standard or strongly-typed).
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<UbjsonValue>> {
+        match self {
+            UbjsonValue::Array(elements) | UbjsonValue::StronglyTypedArray { elements, .. } => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Get the entries of an object (standard or strongly-typed).
+    pub fn as_object(&self) -> Option<&HashMap<String, UbjsonValue>> {
+        match self {
+            UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => Some(pairs),
+            _ => None,
+        }
+    }
+
+    /// Get mutable access to the entries of an object (standard or strongly-typed).
+    pub fn as_object_mut(&mut self) -> Option<&mut HashMap<String, UbjsonValue>> {
+        match self {
+            UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => Some(pairs),
+            _ => None,
+        }
+    }
+
+    /// Iterate over the elements of an array, or nothing for other values.
+    pub fn elements(&self) -> impl Iterator<Item = &UbjsonValue> {
+        self.as_array().into_iter().flatten()
+    }
+
+    /// Iterate mutably over the elements of an array, or nothing for other values.
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut UbjsonValue> {
+        self.as_array_mut().into_iter().flatten()
+    }
+
+    /// Iterate over the entries of an object, or nothing for other values.
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &UbjsonValue)> {
+        self.as_object().into_iter().flatten()
+    }
+
+    /// Iterate mutably over the entries of an object, or nothing for other values.
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = (&String, &mut UbjsonValue)> {
+        self.as_object_mut().into_iter().flatten()
+    }
+
     /// Get a human-readable type name for this value.
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -195,8 +320,14 @@ impl UbjsonValue {
     }
 
     /// Convert a character to UbjsonValue.
+    ///
+    /// Characters outside ASCII do not fit a UBJSON char and become a string.
     pub fn from_char(value: char) -> Self {
-        UbjsonValue::Char(value)
+        if value.is_ascii() {
+            UbjsonValue::Char(value)
+        } else {
+            UbjsonValue::String(value.to_string())
+        }
     }
 
     /// Create an empty array.
@@ -210,6 +341,8 @@ impl UbjsonValue {
     }
 
     /// Create a strongly-typed array with the given element type.
+    ///
+    /// The elements are not checked; use [`try_strongly_typed_array`](Self::try_strongly_typed_array) for that.
     pub fn strongly_typed_array(element_type: UbjsonType, elements: Vec<UbjsonValue>) -> Self {
         UbjsonValue::StronglyTypedArray {
             element_type,
@@ -219,6 +352,8 @@ impl UbjsonValue {
     }
 
     /// Create a strongly-typed object with the given value type.
+    ///
+    /// The values are not checked; use [`try_strongly_typed_object`](Self::try_strongly_typed_object) for that.
     pub fn strongly_typed_object(
         value_type: UbjsonType,
         pairs: HashMap<String, UbjsonValue>,
@@ -298,15 +433,159 @@ impl From<&str> for UbjsonValue {
     }
 }
 
-impl From<Vec<UbjsonValue>> for UbjsonValue {
-    fn from(value: Vec<UbjsonValue>) -> Self {
-        UbjsonValue::Array(value)
+impl<T: Into<UbjsonValue>> From<Vec<T>> for UbjsonValue {
+    fn from(value: Vec<T>) -> Self {
+        UbjsonValue::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<UbjsonValue>> From<&[T]> for UbjsonValue {
+    fn from(value: &[T]) -> Self {
+        UbjsonValue::Array(value.iter().cloned().map(Into::into).collect())
+    }
+}
+
+/// `None` becomes `Null`.
+impl<T: Into<UbjsonValue>> From<Option<T>> for UbjsonValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(UbjsonValue::Null, Into::into)
+    }
+}
+
+impl<T: Into<UbjsonValue>> From<HashMap<String, T>> for UbjsonValue {
+    fn from(value: HashMap<String, T>) -> Self {
+        UbjsonValue::Object(value.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+impl<T: Into<UbjsonValue>> From<BTreeMap<String, T>> for UbjsonValue {
+    fn from(value: BTreeMap<String, T>) -> Self {
+        UbjsonValue::Object(value.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+// Tuples become arrays, as they do with serde
+macro_rules! from_tuple {
+    ($(($($name:ident),+)),*) => {
+        $(
+            impl<$($name: Into<UbjsonValue>),+> From<($($name,)+)> for UbjsonValue {
+                #[allow(non_snake_case)]
+                fn from(($($name,)+): ($($name,)+)) -> Self {
+                    UbjsonValue::Array(vec![$($name.into()),+])
+                }
+            }
+        )*
+    };
+}
+
+from_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+);
+
+/// Collects values into an array. Map other items with `UbjsonValue::from` first.
+impl FromIterator<UbjsonValue> for UbjsonValue {
+    fn from_iter<I: IntoIterator<Item = UbjsonValue>>(iter: I) -> Self {
+        UbjsonValue::Array(iter.into_iter().collect())
+    }
+}
+
+/// Collects key-value pairs into an object. Later values replace earlier ones with the same key.
+impl<K: Into<String>, V: Into<UbjsonValue>> FromIterator<(K, V)> for UbjsonValue {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        UbjsonValue::Object(iter.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
     }
 }
 
-impl From<HashMap<String, UbjsonValue>> for UbjsonValue {
-    fn from(value: HashMap<String, UbjsonValue>) -> Self {
-        UbjsonValue::Object(value)
+// Implement TryFrom for reading primitive Rust types back out
+
+macro_rules! try_from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<UbjsonValue> for $ty {
+                type Error = UbjsonError;
+
+                /// Accepts any integer, or a high-precision number holding one, that fits.
+                fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+                    let n = match &value {
+                        // Parse directly so that u128 values above i128::MAX work
+                        UbjsonValue::HighPrecision(s) => s.parse().ok(),
+                        _ => value.as_i128().and_then(|n| <$ty>::try_from(n).ok()),
+                    };
+                    n.ok_or_else(|| UbjsonError::invalid_type(stringify!($ty), &value))
+                }
+            }
+        )*
+    };
+}
+
+try_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+impl TryFrom<UbjsonValue> for bool {
+    type Error = UbjsonError;
+
+    fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+        value.as_bool().ok_or_else(|| UbjsonError::invalid_type("bool", &value))
+    }
+}
+
+impl TryFrom<UbjsonValue> for f32 {
+    type Error = UbjsonError;
+
+    /// Accepts any number, rounding it to the nearest `f32`.
+    fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+        match value {
+            UbjsonValue::Float32(f) => Ok(f),
+            _ => value.as_f64().map(|f| f as f32).ok_or_else(|| UbjsonError::invalid_type("f32", &value)),
+        }
+    }
+}
+
+impl TryFrom<UbjsonValue> for f64 {
+    type Error = UbjsonError;
+
+    /// Accepts any number, as [`UbjsonValue::as_f64`] does.
+    fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+        value.as_f64().ok_or_else(|| UbjsonError::invalid_type("f64", &value))
+    }
+}
+
+impl TryFrom<UbjsonValue> for char {
+    type Error = UbjsonError;
+
+    /// Accepts a char, or a string holding exactly one character.
+    fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+        if let Some(c) = value.as_char() {
+            return Ok(c);
+        }
+        let mut chars = value.as_str().unwrap_or_default().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(UbjsonError::invalid_type("char", &value)),
+        }
+    }
+}
+
+impl TryFrom<UbjsonValue> for String {
+    type Error = UbjsonError;
+
+    /// Accepts a string or a char.
+    fn try_from(value: UbjsonValue) -> Result<Self, Self::Error> {
+        match value {
+            UbjsonValue::String(s) => Ok(s),
+            UbjsonValue::Char(c) => Ok(c.to_string()),
+            _ => Err(UbjsonError::invalid_type("string", &value)),
+        }
     }
 }
 
@@ -0,0 +1,130 @@
+//! Tests for ObjectBuilder and the conversions into UbjsonValue.
+
+use std::collections::{BTreeMap, HashMap};
+use ubjson_rs::{value_from_slice, value_to_vec, ObjectBuilder, UbjsonError, UbjsonType, UbjsonValue};
+
+#[test]
+fn test_object_builder() {
+    let value = ObjectBuilder::new()
+        .insert("a", 1)
+        .insert("b", "text")
+        .insert("a", 2i64)
+        .insert_typed_array("xs", UbjsonType::Float32, vec![1.0f32, 2.5])
+        .insert_typed_object("ids", UbjsonType::UInt8, [("x", 1u8), ("y", 2u8)])
+        .insert("nested", ObjectBuilder::new().insert("none", None::<i32>))
+        .build();
+
+    let mut expected = HashMap::new();
+    expected.insert("a".to_string(), UbjsonValue::Int64(2));
+    expected.insert("b".to_string(), UbjsonValue::String("text".to_string()));
+    expected.insert(
+        "xs".to_string(),
+        UbjsonValue::strongly_typed_array(UbjsonType::Float32, vec![UbjsonValue::Float32(1.0), UbjsonValue::Float32(2.5)]),
+    );
+    expected.insert(
+        "ids".to_string(),
+        UbjsonValue::strongly_typed_object(
+            UbjsonType::UInt8,
+            [("x".to_string(), UbjsonValue::UInt8(1)), ("y".to_string(), UbjsonValue::UInt8(2))].into_iter().collect(),
+        ),
+    );
+    let mut nested = HashMap::new();
+    nested.insert("none".to_string(), UbjsonValue::Null);
+    expected.insert("nested".to_string(), UbjsonValue::Object(nested));
+    assert_eq!(value, UbjsonValue::Object(expected));
+
+    let bytes = value_to_vec(&value).unwrap();
+    assert_eq!(value_from_slice(&bytes).unwrap(), value);
+    assert_eq!(ObjectBuilder::new().build(), UbjsonValue::empty_object());
+}
+
+#[test]
+fn test_object_builder_coerces_typed_containers() {
+    let value = ObjectBuilder::new()
+        .insert_typed_array("bytes", UbjsonType::Int8, vec![1, 2, 3])
+        .insert_typed_array("wide", UbjsonType::Float64, vec![UbjsonValue::Float32(0.5), UbjsonValue::Int8(2)])
+        .insert_typed_array("mixed", UbjsonType::Int8, vec![UbjsonValue::Int8(1), UbjsonValue::Null])
+        .insert_typed_object("ids", UbjsonType::UInt8, [("x", 300), ("y", 2)])
+        .build();
+    assert_eq!(
+        value.pointer("/bytes").unwrap(),
+        &UbjsonValue::strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2), UbjsonValue::Int8(3)])
+    );
+    assert_eq!(
+        value.pointer("/wide").unwrap(),
+        &UbjsonValue::strongly_typed_array(UbjsonType::Float64, vec![UbjsonValue::Float64(0.5), UbjsonValue::Float64(2.0)])
+    );
+    // Values that don't fit leave a plain container
+    assert_eq!(value.pointer("/mixed").unwrap(), &UbjsonValue::Array(vec![UbjsonValue::Int8(1), UbjsonValue::Null]));
+    assert_eq!(
+        value.pointer("/ids").unwrap(),
+        &UbjsonValue::Object(HashMap::from([
+            ("x".to_string(), UbjsonValue::Int32(300)),
+            ("y".to_string(), UbjsonValue::UInt8(2)),
+        ]))
+    );
+}
+
+#[test]
+fn test_object_builder_try_insert_checks_typed_containers() {
+    let value = ObjectBuilder::new()
+        .try_insert_typed_array("bytes", UbjsonType::UInt8, vec![1, 2])
+        .unwrap()
+        .try_insert_typed_object("xs", UbjsonType::Float32, [("a", 1.5f64)])
+        .unwrap()
+        .build();
+    assert_eq!(
+        value.pointer("/bytes").unwrap(),
+        &UbjsonValue::strongly_typed_array(UbjsonType::UInt8, vec![UbjsonValue::UInt8(1), UbjsonValue::UInt8(2)])
+    );
+    assert_eq!(
+        value.pointer("/xs").unwrap(),
+        &UbjsonValue::strongly_typed_object(UbjsonType::Float32, HashMap::from([("a".to_string(), UbjsonValue::Float32(1.5))]))
+    );
+
+    let err = ObjectBuilder::new()
+        .try_insert_typed_array("xs", UbjsonType::Int8, vec![UbjsonValue::Int8(1), UbjsonValue::Int16(300)])
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::TypedContainerMismatch { expected: UbjsonType::Int8, found: UbjsonType::Int16 }));
+    let err = ObjectBuilder::new().try_insert_typed_object("xs", UbjsonType::ArrayStart, [("a", 1)]).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidFormat(_)));
+}
+
+#[test]
+fn test_from_collections() {
+    let ints = UbjsonValue::Array(vec![UbjsonValue::Int32(1), UbjsonValue::Int32(2)]);
+    assert_eq!(UbjsonValue::from(vec![1, 2]), ints);
+    assert_eq!(UbjsonValue::from(&[1, 2][..]), ints);
+    assert_eq!(UbjsonValue::from(vec![Some(1), None]), UbjsonValue::Array(vec![UbjsonValue::Int32(1), UbjsonValue::Null]));
+    assert_eq!(UbjsonValue::from(Some("a")), UbjsonValue::String("a".to_string()));
+    assert_eq!(UbjsonValue::from(None::<bool>), UbjsonValue::Null);
+    assert_eq!(UbjsonValue::from(vec![vec![1]]), UbjsonValue::Array(vec![UbjsonValue::Array(vec![UbjsonValue::Int32(1)])]));
+
+    let mut map = HashMap::new();
+    map.insert("x".to_string(), 1.5f64);
+    let object = UbjsonValue::from(map);
+    assert_eq!(object.as_object().unwrap()["x"], UbjsonValue::Float64(1.5));
+    let mut tree = BTreeMap::new();
+    tree.insert("x".to_string(), 1.5f64);
+    assert_eq!(UbjsonValue::from(tree), object);
+
+    assert_eq!(
+        UbjsonValue::from((1u8, "a", true)),
+        UbjsonValue::Array(vec![UbjsonValue::UInt8(1), UbjsonValue::String("a".to_string()), UbjsonValue::Bool(true)])
+    );
+    assert_eq!(UbjsonValue::from((1i8,)), UbjsonValue::Array(vec![UbjsonValue::Int8(1)]));
+}
+
+#[test]
+fn test_from_iterator() {
+    let array: UbjsonValue = (0..3i16).map(UbjsonValue::from).collect();
+    assert_eq!(array, UbjsonValue::Array(vec![UbjsonValue::Int16(0), UbjsonValue::Int16(1), UbjsonValue::Int16(2)]));
+
+    let object: UbjsonValue = [("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+    let pairs = object.as_object().unwrap();
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs["a"], UbjsonValue::Int32(3));
+
+    let empty: UbjsonValue = std::iter::empty::<UbjsonValue>().collect();
+    assert_eq!(empty, UbjsonValue::empty_array());
+}
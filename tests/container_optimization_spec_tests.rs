@@ -3,7 +3,7 @@
 //! These tests ensure that both serializer and deserializer comply with the
 //! container optimization specification requirements.
 
-use ubjson_rs::{UbjsonSerializer, UbjsonDeserializer, UbjsonValue, UbjsonType};
+use ubjson_rs::{UbjsonError, UbjsonSerializer, UbjsonDeserializer, UbjsonValue, UbjsonType};
 use std::collections::HashMap;
 use std::io::Cursor;
 
@@ -149,16 +149,14 @@ fn test_serializer_container_must_contain_specified_count() {
         ],
     };
     
-    // This should succeed - the serializer uses the actual elements length
-    // and ignores the count field for validation (it's the deserializer's job)
+    // The mismatch is rejected before anything is written
     let result = serializer.serialize_value(&array);
-    assert!(result.is_ok());
-    
-    // The serialized count should match the actual elements (3, not 2)
-    assert_eq!(buffer[5], 3); // Count should be 3
+    assert!(matches!(result, Err(UbjsonError::LengthMismatch { expected: 2, actual: 3 })));
+    assert!(buffer.is_empty());
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_serializer_type_specified_before_count() {
     // Test that type marker comes before count marker in serialized output
     
@@ -305,6 +305,7 @@ fn test_empty_containers_no_optimization() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_single_element_array_optimization() {
     let mut buffer = Vec::new();
     let mut serializer = UbjsonSerializer::with_optimization(&mut buffer, true);
@@ -1,5 +1,5 @@
 use std::io::Cursor;
-use ubjson_rs::{UbjsonDeserializer, UbjsonValue, UbjsonError};
+use ubjson_rs::{UbjsonDeserializer, UbjsonValue, UbjsonError, UbjsonType};
 
 #[test]
 fn test_deserialize_all_primitive_types() {
@@ -279,14 +279,14 @@ fn test_unexpected_container_end_markers() {
     let mut deserializer = UbjsonDeserializer::new(Cursor::new(data));
     let result = deserializer.deserialize_value();
     assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), UbjsonError::InvalidFormat(_)));
+    assert!(matches!(result.unwrap_err(), UbjsonError::UnexpectedContainerEnd(UbjsonType::ArrayEnd)));
 
     // Test object end marker without start
     let data = vec![b'}'];
     let mut deserializer = UbjsonDeserializer::new(Cursor::new(data));
     let result = deserializer.deserialize_value();
     assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), UbjsonError::InvalidFormat(_)));
+    assert!(matches!(result.unwrap_err(), UbjsonError::UnexpectedContainerEnd(UbjsonType::ObjectEnd)));
 }
 
 #[test]
@@ -333,6 +333,7 @@ fn test_container_error_conditions() {
 }
 
 #[test]
+#[allow(clippy::same_item_push)]
 fn test_container_depth_limits() {
     // Test array depth limit
     let mut data = vec![];
@@ -520,6 +521,7 @@ fn test_complex_nested_containers() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_round_trip_with_serializer() {
     use ubjson_rs::UbjsonSerializer;
     use std::io::Cursor;
@@ -549,12 +551,21 @@ fn test_round_trip_with_serializer() {
         let mut serializer = UbjsonSerializer::new(&mut buffer);
         serializer.serialize_value(&original_value).unwrap();
 
+        // A char outside ASCII doesn't fit in one byte, so it is written as a string
+        let expected_value = match original_value {
+            UbjsonValue::Char(c) if !c.is_ascii() => {
+                assert_eq!(buffer[0], b'S');
+                UbjsonValue::String(c.to_string())
+            }
+            value => value,
+        };
+
         // Deserialize
         let mut deserializer = UbjsonDeserializer::new(Cursor::new(buffer));
         let deserialized_value = deserializer.deserialize_value().unwrap();
 
         // Compare
-        assert_eq!(original_value, deserialized_value);
+        assert_eq!(expected_value, deserialized_value);
     }
 
     // Test containers
@@ -998,6 +1009,7 @@ fn test_deserialize_object_level_6_deeply_nested() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_deserialize_object_level_7_all_data_types() {
     // Level 7: Object containing all supported UBJSON data types
     // {
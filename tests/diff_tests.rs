@@ -0,0 +1,112 @@
+//! Tests for structural diffs and the `ubjson diff` command.
+
+use std::collections::HashMap;
+use std::process::Command;
+use ubjson_rs::{diff, value_to_vec, Change, Patch, PathSegment, UbjsonType, UbjsonValue};
+
+fn object(pairs: &[(&str, UbjsonValue)]) -> UbjsonValue {
+    UbjsonValue::Object(pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect())
+}
+
+fn key(k: &str) -> PathSegment {
+    PathSegment::Key(k.to_string())
+}
+
+fn old() -> UbjsonValue {
+    object(&[
+        ("id", UbjsonValue::Int16(5)),
+        ("name", UbjsonValue::String("a".to_string())),
+        ("gone", UbjsonValue::Bool(true)),
+        ("list", UbjsonValue::Array(vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2), UbjsonValue::Int8(3)])),
+        ("ids", UbjsonValue::Array(vec![UbjsonValue::UInt8(1), UbjsonValue::UInt8(2)])),
+    ])
+}
+
+fn new() -> UbjsonValue {
+    object(&[
+        ("id", UbjsonValue::Int32(5)),
+        ("name", UbjsonValue::String("b".to_string())),
+        ("extra", UbjsonValue::Null),
+        ("list", UbjsonValue::Array(vec![UbjsonValue::Int8(1)])),
+        ("ids", UbjsonValue::strongly_typed_array(UbjsonType::UInt8, vec![UbjsonValue::UInt8(1), UbjsonValue::UInt8(2)])),
+    ])
+}
+
+#[test]
+fn test_identical_values_have_no_changes() {
+    assert!(diff(&old(), &old()).is_empty());
+    let nan = UbjsonValue::Array(vec![UbjsonValue::Float64(f64::NAN)]);
+    assert!(diff(&nan, &nan).is_empty());
+}
+
+#[test]
+fn test_diff_reports_changes_with_paths() {
+    let changes = diff(&old(), &new());
+    assert_eq!(changes, vec![
+        Change::Added { path: vec![key("extra")], value: UbjsonValue::Null },
+        Change::Removed { path: vec![key("gone")], value: UbjsonValue::Bool(true) },
+        Change::EncodingChanged { path: vec![key("id")], old: UbjsonValue::Int16(5), new: UbjsonValue::Int32(5) },
+        Change::EncodingChanged { path: vec![key("ids")], old: old().as_object().unwrap()["ids"].clone(), new: new().as_object().unwrap()["ids"].clone() },
+        Change::Removed { path: vec![key("list"), PathSegment::Index(2)], value: UbjsonValue::Int8(3) },
+        Change::Removed { path: vec![key("list"), PathSegment::Index(1)], value: UbjsonValue::Int8(2) },
+        Change::Changed {
+            path: vec![key("name")],
+            old: UbjsonValue::String("a".to_string()),
+            new: UbjsonValue::String("b".to_string()),
+        },
+    ]);
+
+    let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+    assert_eq!(lines[2], "= $.id: int16 -> int32");
+    assert_eq!(lines[3], "= $.ids: array -> strongly_typed_array of uint8");
+    assert_eq!(lines[4], "- $.list[2]: 3");
+}
+
+#[test]
+fn test_diff_converts_to_patch() {
+    let changes = diff(&old(), &new());
+    let patch = Patch::from(changes.as_slice());
+    let mut value = old();
+    value.apply_patch(&patch).unwrap();
+    assert_eq!(value, new());
+
+    // Nested typed containers with different element types are replaced whole
+    let mut pairs = HashMap::new();
+    pairs.insert("x".to_string(), UbjsonValue::strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(1)]));
+    let a = UbjsonValue::Object(pairs);
+    let b = object(&[("x", UbjsonValue::strongly_typed_array(UbjsonType::Int16, vec![UbjsonValue::Int16(2)]))]);
+    let changes = diff(&a, &b);
+    assert_eq!(changes.len(), 2);
+    let mut value = a.clone();
+    value.apply_patch(&Patch::from(changes.as_slice())).unwrap();
+    assert_eq!(value, b);
+}
+
+#[test]
+fn test_diff_command() {
+    let dir = std::env::temp_dir().join(format!("ubjson-diff-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let old_path = dir.join("old.ubj");
+    let new_path = dir.join("new.ubj");
+    std::fs::write(&old_path, value_to_vec(&old()).unwrap()).unwrap();
+    std::fs::write(&new_path, value_to_vec(&new()).unwrap()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ubjson"))
+        .args(["diff", old_path.to_str().unwrap(), new_path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.lines().any(|line| line == "~ $.name: \"a\" -> \"b\""), "{}", stdout);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ubjson"))
+        .args(["diff", old_path.to_str().unwrap(), old_path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ubjson")).arg("diff").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,86 @@
+//! Tests for the duplicate object key policy.
+
+use ubjson_rs::{DeserializerBuilder, DuplicateKeys, UbjsonError, UbjsonValue, WarningKind};
+
+const STANDARD: &[u8] = b"{U\x05adminFU\x04nameSU\x01xU\x05adminT}";
+const TYPED: &[u8] = b"{$i#U\x03U\x01a\x01U\x01b\x02U\x01a\x03";
+
+fn decode(bytes: &[u8], policy: DuplicateKeys) -> Result<UbjsonValue, UbjsonError> {
+    DeserializerBuilder::new().with_duplicate_keys(policy).value_from_slice(bytes)
+}
+
+fn object_field(value: &UbjsonValue, key: &str) -> UbjsonValue {
+    match value {
+        UbjsonValue::Object(pairs) | UbjsonValue::StronglyTypedObject { pairs, .. } => pairs[key].clone(),
+        other => panic!("expected an object, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_duplicate_keys_error_by_default() {
+    let err = DeserializerBuilder::new().value_from_slice(STANDARD).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidFormat(_)));
+    assert!(matches!(decode(TYPED, DuplicateKeys::Error), Err(UbjsonError::InvalidFormat(_))));
+}
+
+#[test]
+fn test_duplicate_keys_first_wins() {
+    let value = decode(STANDARD, DuplicateKeys::FirstWins).unwrap();
+    assert_eq!(object_field(&value, "admin"), UbjsonValue::Bool(false));
+    assert_eq!(value.len(), Some(2));
+
+    let value = decode(TYPED, DuplicateKeys::FirstWins).unwrap();
+    assert_eq!(object_field(&value, "a"), UbjsonValue::Int8(1));
+    assert_eq!(value.len(), Some(2));
+}
+
+#[test]
+fn test_duplicate_keys_last_wins() {
+    let value = decode(STANDARD, DuplicateKeys::LastWins).unwrap();
+    assert_eq!(object_field(&value, "admin"), UbjsonValue::Bool(true));
+
+    let value = DeserializerBuilder::new()
+        .with_duplicate_keys(DuplicateKeys::LastWins)
+        .value_from_reader(TYPED)
+        .unwrap();
+    assert_eq!(object_field(&value, "a"), UbjsonValue::Int8(3));
+}
+
+#[test]
+fn test_duplicate_key_policy_recorded_in_lenient_mode() {
+    let (value, warnings) = DeserializerBuilder::new()
+        .lenient(true)
+        .with_duplicate_keys(DuplicateKeys::FirstWins)
+        .value_from_slice_with_warnings(STANDARD)
+        .unwrap();
+    assert_eq!(object_field(&value, "admin"), UbjsonValue::Bool(false));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::DuplicateKey("admin".to_string()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_duplicate_key_policy_applies_to_serde_maps() {
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize)]
+    struct User {
+        admin: bool,
+    }
+
+    let result: Result<User, _> = DeserializerBuilder::new().from_slice(STANDARD);
+    assert!(matches!(result, Err(UbjsonError::InvalidFormat(_))));
+
+    let user: User = DeserializerBuilder::new()
+        .with_duplicate_keys(DuplicateKeys::FirstWins)
+        .from_slice(STANDARD)
+        .unwrap();
+    assert!(!user.admin);
+
+    let map: HashMap<String, i8> = DeserializerBuilder::new()
+        .with_duplicate_keys(DuplicateKeys::LastWins)
+        .from_slice(TYPED)
+        .unwrap();
+    assert_eq!(map["a"], 3);
+}
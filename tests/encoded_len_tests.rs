@@ -0,0 +1,30 @@
+//! Tests for computing encoded sizes of serde values without writing.
+
+#![cfg(feature = "serde")]
+
+use serde::Serialize;
+use ubjson_rs::{SerializerBuilder, UbjsonError};
+
+#[test]
+fn test_encoded_len_of_serde_values() {
+    #[derive(Serialize)]
+    struct Frame {
+        id: u64,
+        label: String,
+        samples: Vec<f32>,
+    }
+
+    let frame = Frame { id: 7, label: "left".to_string(), samples: vec![0.5; 64] };
+    for optimize in [false, true] {
+        let builder = SerializerBuilder::new().with_container_optimization(optimize);
+        let bytes = builder.clone().to_vec(&frame).unwrap();
+        assert_eq!(builder.encoded_len(&frame).unwrap(), bytes.len());
+    }
+}
+
+#[test]
+fn test_encoded_len_reports_depth_errors() {
+    let nested = vec![vec![vec![ubjson_rs::UbjsonValue::Null]]];
+    let err = SerializerBuilder::new().with_max_depth(2).encoded_len(&nested).unwrap_err();
+    assert!(matches!(err, UbjsonError::DepthLimitExceeded(2)));
+}
@@ -0,0 +1,110 @@
+use std::io;
+use ubjson_rs::{value_from_reader, value_from_slice, value_to_vec, ErrorKind, UbjsonError, UbjsonType, UbjsonValue};
+
+#[test]
+fn test_truncated_input_is_eof_for_every_source() {
+    let bytes = value_to_vec(&UbjsonValue::Array(vec![
+        UbjsonValue::Int32(1),
+        UbjsonValue::String("truncated".to_string()),
+    ]))
+    .unwrap();
+
+    for cut in 1..bytes.len() {
+        let truncated = &bytes[..cut];
+
+        let err = value_from_slice(truncated).unwrap_err();
+        assert!(matches!(err, UbjsonError::UnexpectedEof), "slice cut at {}: {:?}", cut, err);
+        assert!(err.is_eof());
+
+        let err = value_from_reader(truncated).unwrap_err();
+        assert!(matches!(err, UbjsonError::UnexpectedEof), "reader cut at {}: {:?}", cut, err);
+        assert_eq!(err.classify(), ErrorKind::Eof);
+    }
+}
+
+#[test]
+fn test_classify_syntax_errors() {
+    let err = value_from_slice(&[0xFF]).unwrap_err();
+    assert_eq!(err.classify(), ErrorKind::Syntax);
+
+    let err = value_from_slice(b"]").unwrap_err();
+    assert!(matches!(err, UbjsonError::UnexpectedContainerEnd(UbjsonType::ArrayEnd)));
+    assert_eq!(err.classify(), ErrorKind::Syntax);
+
+    let err = value_from_slice(&[b'S', b'i', 0xFE]).unwrap_err();
+    assert!(matches!(err, UbjsonError::NegativeLength(-2)));
+    assert_eq!(err.classify(), ErrorKind::Syntax);
+    assert!(!err.is_eof());
+}
+
+#[test]
+fn test_classify_limit_and_data_errors() {
+    let err = ubjson_rs::DeserializerBuilder::new()
+        .with_max_depth(1)
+        .value_from_slice(b"[[Z]]")
+        .unwrap_err();
+    assert_eq!(err.classify(), ErrorKind::Limit);
+
+    let err = value_from_slice(&[b'S', b'U', 1, 0xFF]).unwrap_err();
+    assert_eq!(err.classify(), ErrorKind::Data);
+
+    let array = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Int8,
+        count: Some(1),
+        elements: vec![UbjsonValue::String("x".to_string())],
+    };
+    let err = value_to_vec(&array).unwrap_err();
+    assert!(matches!(
+        err,
+        UbjsonError::TypedContainerMismatch { expected: UbjsonType::Int8, found: UbjsonType::String }
+    ));
+    assert_eq!(err.classify(), ErrorKind::Data);
+}
+
+#[test]
+fn test_classify_io_and_custom_errors() {
+    struct FailingReader;
+
+    impl io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"))
+        }
+    }
+
+    let err = value_from_reader(FailingReader).unwrap_err();
+    assert_eq!(err.classify(), ErrorKind::Io);
+
+    let err = UbjsonError::serde("custom message");
+    assert_eq!(err.classify(), ErrorKind::Custom);
+}
+
+#[test]
+fn test_io_error_conversions() {
+    let err: UbjsonError = io::Error::from(io::ErrorKind::UnexpectedEof).into();
+    assert!(matches!(err, UbjsonError::UnexpectedEof));
+
+    let err: UbjsonError = io::Error::from(io::ErrorKind::BrokenPipe).into();
+    assert!(matches!(&err, UbjsonError::Io(e) if e.kind() == io::ErrorKind::BrokenPipe));
+    let io_err: io::Error = err.into();
+    assert_eq!(io_err.kind(), io::ErrorKind::BrokenPipe);
+
+    let io_err: io::Error = UbjsonError::UnexpectedEof.into();
+    assert_eq!(io_err.kind(), io::ErrorKind::UnexpectedEof);
+
+    let io_err: io::Error = UbjsonError::InvalidTypeMarker(0xFF).into();
+    assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+    let inner = io_err.into_inner().unwrap().downcast::<UbjsonError>().unwrap();
+    assert!(matches!(*inner, UbjsonError::InvalidTypeMarker(0xFF)));
+
+    let io_err: io::Error = UbjsonError::serde("custom").into();
+    assert_eq!(io_err.kind(), io::ErrorKind::Other);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_type_mismatch_is_invalid_type() {
+    let bytes = ubjson_rs::to_vec(&"not a number").unwrap();
+    let err = ubjson_rs::from_slice::<i32>(&bytes).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidType { expected: "i32", found: "string" }));
+    assert_eq!(err.classify(), ErrorKind::Data);
+}
@@ -0,0 +1,153 @@
+//! Tests for streaming extraction of query matches.
+
+use std::collections::HashMap;
+use ubjson_rs::{
+    format_path, value_from_slice, value_to_vec, DeserializerBuilder, PathSegment, Query, SerializerBuilder, UbjsonError, UbjsonType,
+    UbjsonValue,
+};
+
+fn object(pairs: Vec<(&str, UbjsonValue)>) -> UbjsonValue {
+    UbjsonValue::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<HashMap<_, _>>())
+}
+
+fn frame(i: i32) -> UbjsonValue {
+    object(vec![
+        ("index", UbjsonValue::Int32(i)),
+        ("note", UbjsonValue::String(format!("frame {}", i))),
+        ("ports", UbjsonValue::Array(vec![
+            object(vec![("x", UbjsonValue::Float32(i as f32)), ("y", UbjsonValue::Float32(-1.0))]),
+            object(vec![("x", UbjsonValue::Float32(i as f32 * 2.0)), ("y", UbjsonValue::Float32(1.0))]),
+        ])),
+        ("inputs", UbjsonValue::strongly_typed_array(UbjsonType::UInt8, vec![UbjsonValue::UInt8(i as u8); 4])),
+        ("tags", UbjsonValue::strongly_typed_object(
+            UbjsonType::String,
+            [("a".to_string(), UbjsonValue::String("x".repeat(i as usize)))].into_iter().collect(),
+        )),
+    ])
+}
+
+fn document(frames: i32) -> UbjsonValue {
+    object(vec![
+        ("version", UbjsonValue::HighPrecision("1.0".to_string())),
+        ("frames", UbjsonValue::Array((0..frames).map(frame).collect())),
+    ])
+}
+
+fn extract(bytes: &[u8], expr: &str) -> Vec<(String, UbjsonValue)> {
+    let mut found = Vec::new();
+    DeserializerBuilder::new()
+        .extract_from_slice(bytes, &Query::parse(expr).unwrap(), |path, value| {
+            found.push((format_path(path), value))
+        })
+        .unwrap();
+    found
+}
+
+fn select(value: &UbjsonValue, expr: &str) -> Vec<(String, UbjsonValue)> {
+    Query::parse(expr)
+        .unwrap()
+        .select_paths(value)
+        .into_iter()
+        .map(|(path, value)| (format_path(&path), value.clone()))
+        .collect()
+}
+
+#[test]
+fn test_extract_matches_in_memory_query() {
+    let value = document(5);
+    for optimize in [false, true] {
+        let bytes = SerializerBuilder::new().with_container_optimization(optimize).value_to_vec(&value).unwrap();
+        let decoded = value_from_slice(&bytes).unwrap();
+        for expr in [
+            "$.frames[*].ports[0].x",
+            "$.frames[1:4:2].index",
+            "$.frames[-1].index",
+            "$.frames[::-2].index",
+            "$.frames[2]",
+            "$..x",
+            "$..inputs[1]",
+            "$.frames[*].tags.a",
+            "$.frames[?(@.index >= 3)].note",
+            "$.frames[*].ports[?(@.y > 0)].x",
+            "$.version",
+            "$",
+            "$.missing[0]",
+        ] {
+            let mut streamed = extract(&bytes, expr);
+            let mut expected = select(&decoded, expr);
+            streamed.sort_by(|a, b| a.0.cmp(&b.0));
+            expected.sort_by(|a, b| a.0.cmp(&b.0));
+            assert_eq!(streamed, expected, "{} (optimized: {})", expr, optimize);
+        }
+    }
+}
+
+#[test]
+fn test_extract_reports_in_stream_order() {
+    let bytes = value_to_vec(&document(3)).unwrap();
+    let paths: Vec<String> = extract(&bytes, "$.frames[*].ports[*].x").into_iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, vec![
+        "$.frames[0].ports[0].x",
+        "$.frames[0].ports[1].x",
+        "$.frames[1].ports[0].x",
+        "$.frames[1].ports[1].x",
+        "$.frames[2].ports[0].x",
+        "$.frames[2].ports[1].x",
+    ]);
+}
+
+#[test]
+fn test_extract_skips_unmatched_values() {
+    // Only the matched values count against the decoding budgets
+    let value = document(200);
+    let bytes = SerializerBuilder::new().with_container_optimization(true).value_to_vec(&value).unwrap();
+    let mut found = Vec::new();
+    DeserializerBuilder::new()
+        .with_max_total_values(10)
+        .with_max_string_length(8)
+        .with_max_size(2)
+        .extract_from_slice(&bytes, &Query::parse("$.frames[150].ports[1].x").unwrap(), |path, value| {
+            found.push((path.to_vec(), value))
+        })
+        .unwrap();
+    let path = vec![
+        PathSegment::Key("frames".to_string()),
+        PathSegment::Index(150),
+        PathSegment::Key("ports".to_string()),
+        PathSegment::Index(1),
+        PathSegment::Key("x".to_string()),
+    ];
+    assert_eq!(found, vec![(path, UbjsonValue::Float32(300.0))]);
+
+    // Decoding the whole document goes over the same budgets
+    assert!(DeserializerBuilder::new().with_max_total_values(10).value_from_slice(&bytes).is_err());
+}
+
+#[test]
+fn test_extract_from_reader() {
+    let bytes = value_to_vec(&document(4)).unwrap();
+    let mut found = Vec::new();
+    DeserializerBuilder::new()
+        .extract_from_reader(bytes.as_slice(), &Query::parse("$.frames[*].index").unwrap(), |_, value| {
+            found.push(value)
+        })
+        .unwrap();
+    assert_eq!(found, (0..4).map(UbjsonValue::Int32).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_extract_errors() {
+    let bytes = value_to_vec(&document(2)).unwrap();
+    let query = Query::parse("$.frames[?(@.index == $.version)]").unwrap();
+    let result = DeserializerBuilder::new().extract_from_slice(&bytes, &query, |_, _| {});
+    assert!(matches!(result, Err(UbjsonError::UnsupportedType(_))));
+
+    let query = Query::parse("$.version").unwrap();
+    let result = DeserializerBuilder::new().extract_from_slice(&bytes[..bytes.len() - 1], &query, |_, _| {});
+    assert!(result.unwrap_err().is_eof());
+
+    let mut trailing = bytes.clone();
+    trailing.push(b'Z');
+    let result = DeserializerBuilder::new().extract_from_slice(&trailing, &query, |_, _| {});
+    assert!(matches!(result, Err(UbjsonError::TrailingData { .. })));
+}
@@ -0,0 +1,168 @@
+//! Tests that declared lengths in hostile headers are not trusted for allocation.
+//!
+//! This test binary installs an allocator that records the largest single
+//! allocation, so every test here must keep its own allocations small.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, Cursor};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use ubjson_rs::encoding::read_string;
+use ubjson_rs::{DeserializerBuilder, UbjsonError};
+
+struct TrackingAllocator;
+
+static LARGEST_ALLOCATION: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LARGEST_ALLOCATION.fetch_max(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LARGEST_ALLOCATION.fetch_max(new_size, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// No allocation in this binary should come anywhere near the declared sizes.
+const ALLOCATION_CEILING: usize = 16 * 1024 * 1024;
+
+fn assert_no_large_allocation() {
+    let largest = LARGEST_ALLOCATION.load(Ordering::Relaxed);
+    assert!(largest < ALLOCATION_CEILING, "largest allocation was {} bytes", largest);
+}
+
+/// Reader that yields its data one byte at a time, like a slow socket.
+struct Trickle<'a>(&'a [u8]);
+
+impl io::Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        Ok(1)
+    }
+}
+
+fn decode_both_ways(bytes: &[u8]) -> (UbjsonError, UbjsonError) {
+    let from_slice = DeserializerBuilder::new().value_from_slice(bytes).unwrap_err();
+    let from_reader = DeserializerBuilder::new().value_from_reader(Trickle(bytes)).unwrap_err();
+    (from_slice, from_reader)
+}
+
+#[test]
+fn test_huge_string_header() {
+    // S L <i64::MAX> followed by a few payload bytes
+    let bytes = [b'S', b'L', 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, b'a', b'b'];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(from_slice.is_eof());
+    assert!(from_reader.is_eof());
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_huge_string_header_low_level() {
+    let bytes = [b'l', 0x7F, 0xFF, 0xFF, 0xFF, b'a'];
+    let err = read_string(&mut Cursor::new(&bytes[..])).unwrap_err();
+    assert!(err.is_eof());
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_huge_high_precision_header() {
+    let bytes = [b'H', b'l', 0x7F, 0xFF, 0xFF, 0xFF, b'1'];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(from_slice.is_eof());
+    assert!(from_reader.is_eof());
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_huge_object_key_header() {
+    let bytes = [b'{', b'l', 0x7F, 0xFF, 0xFF, 0xFF, b'k', b'e', b'y'];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(from_slice.is_eof());
+    assert!(from_reader.is_eof());
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_truncated_char() {
+    // First byte of a four-byte UTF-8 sequence with nothing after it
+    let bytes = [b'C', 0xF0];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(from_slice.is_eof());
+    assert!(from_reader.is_eof());
+}
+
+#[test]
+fn test_huge_counted_array_header() {
+    // [ $ Z # L <i64::MAX> declares nulls, which need no payload bytes at all
+    let bytes = [b'[', b'$', b'Z', b'#', b'L', 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(matches!(from_slice, UbjsonError::SizeLimitExceeded(1_000_000)));
+    assert!(matches!(from_reader, UbjsonError::SizeLimitExceeded(1_000_000)));
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_counted_typed_array_within_size_limit() {
+    // [ $ l # l <1_000_000> : a count the size limit allows, but no elements behind it
+    let bytes = [b'[', b'$', b'l', b'#', b'l', 0x00, 0x0F, 0x42, 0x40, 0, 0, 0, 1];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(from_slice.is_eof());
+    assert!(from_reader.is_eof());
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_counted_typed_object_within_size_limit() {
+    let bytes = [b'{', b'$', b'D', b'#', b'l', 0x00, 0x0F, 0x42, 0x40, b'U', 1, b'k'];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(from_slice.is_eof());
+    assert!(from_reader.is_eof());
+    assert_no_large_allocation();
+}
+
+#[test]
+fn test_counted_typed_array_over_size_limit() {
+    let bytes = [b'[', b'$', b'i', b'#', b'l', 0x00, 0x0F, 0x42, 0x41];
+    let (from_slice, from_reader) = decode_both_ways(&bytes);
+    assert!(matches!(from_slice, UbjsonError::SizeLimitExceeded(1_000_000)));
+    assert!(matches!(from_reader, UbjsonError::SizeLimitExceeded(1_000_000)));
+}
+
+#[test]
+fn test_string_length_limit_rejects_header() {
+    let bytes = [b'S', b'l', 0x00, 0x10, 0x00, 0x00, b'a'];
+    let err = DeserializerBuilder::new()
+        .with_max_string_length(4096)
+        .value_from_reader(Trickle(&bytes))
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::StringLengthLimitExceeded(4096)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_hostile_headers_through_serde() {
+    let bytes = [b'S', b'L', 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, b'a'];
+    let err = DeserializerBuilder::new().from_slice::<String>(&bytes).unwrap_err();
+    assert!(err.is_eof());
+
+    let bytes = [b'[', b'$', b'l', b'#', b'l', 0x00, 0x0F, 0x42, 0x40, 0, 0, 0, 1];
+    let err = DeserializerBuilder::new()
+        .from_reader::<_, Vec<i32>>(Trickle(&bytes))
+        .unwrap_err();
+    assert!(err.is_eof());
+    assert_no_large_allocation();
+}
@@ -0,0 +1,104 @@
+//! Tests for conversions to and from serde_json values.
+
+#![cfg(feature = "serde_json")]
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use ubjson_rs::{UbjsonError, UbjsonType, UbjsonValue};
+
+#[test]
+fn test_from_json_value() {
+    let value = UbjsonValue::from(json!({
+        "name": "ubjson",
+        "count": -3,
+        "big": 18446744073709551615u64,
+        "ratio": 0.25,
+        "tags": [true, null],
+    }));
+
+    let mut expected = HashMap::new();
+    expected.insert("name".to_string(), UbjsonValue::String("ubjson".to_string()));
+    expected.insert("count".to_string(), UbjsonValue::Int64(-3));
+    expected.insert("big".to_string(), UbjsonValue::HighPrecision("18446744073709551615".to_string()));
+    expected.insert("ratio".to_string(), UbjsonValue::Float64(0.25));
+    expected.insert("tags".to_string(), UbjsonValue::Array(vec![UbjsonValue::Bool(true), UbjsonValue::Null]));
+    assert_eq!(value, UbjsonValue::Object(expected));
+}
+
+#[test]
+fn test_to_json_value() {
+    let mut pairs = HashMap::new();
+    pairs.insert("x".to_string(), UbjsonValue::Int16(300));
+    let value = UbjsonValue::Array(vec![
+        UbjsonValue::UInt8(255),
+        UbjsonValue::Float32(0.1),
+        UbjsonValue::Char('c'),
+        UbjsonValue::HighPrecision("-42".to_string()),
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Int8,
+            count: None,
+            elements: vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2)],
+        },
+        UbjsonValue::StronglyTypedObject { value_type: UbjsonType::Int16, count: Some(1), pairs },
+    ]);
+    assert_eq!(
+        Value::try_from(value).unwrap(),
+        json!([255, 0.1, "c", -42, [1, 2], {"x": 300}])
+    );
+}
+
+#[test]
+fn test_non_finite_floats_are_rejected() {
+    for value in [
+        UbjsonValue::Float64(f64::NAN),
+        UbjsonValue::Float32(f32::INFINITY),
+        UbjsonValue::Array(vec![UbjsonValue::Float64(f64::NEG_INFINITY)]),
+    ] {
+        let err = Value::try_from(value).unwrap_err();
+        assert!(matches!(err, UbjsonError::UnsupportedType(_)), "{:?}", err);
+    }
+}
+
+#[test]
+fn test_invalid_high_precision_is_rejected() {
+    for text in ["+12", "1.", "abc"] {
+        let err = Value::try_from(UbjsonValue::HighPrecision(text.to_string())).unwrap_err();
+        assert!(matches!(err, UbjsonError::InvalidHighPrecision(_)), "{}: {:?}", text, err);
+    }
+}
+
+#[test]
+fn test_json_round_trip() {
+    let original = json!({"a": [1, -2, 3.5, "s", {"b": null}], "c": false, "d": 12345678901234567890u64});
+    let value = UbjsonValue::from(original.clone());
+    let bytes = ubjson_rs::value_to_vec(&value).unwrap();
+    let decoded = ubjson_rs::value_from_slice(&bytes).unwrap();
+    assert_eq!(Value::try_from(decoded).unwrap(), original);
+}
+
+#[test]
+fn test_deep_values_do_not_overflow() {
+    let depth = 200_000;
+    let mut json = json!(1);
+    for _ in 0..depth {
+        json = Value::Array(vec![json]);
+    }
+
+    let value = UbjsonValue::from(json);
+    let mut levels = 0;
+    let mut inner = &value;
+    while let Some(elements) = inner.as_array() {
+        inner = &elements[0];
+        levels += 1;
+    }
+    assert_eq!(levels, depth);
+    assert_eq!(inner, &UbjsonValue::Int64(1));
+
+    let mut json = Value::try_from(value).unwrap();
+    // Take the tree apart without recursion so dropping it can't overflow either
+    while let Some(inner) = json.as_array_mut().and_then(Vec::pop) {
+        json = inner;
+    }
+    assert_eq!(json, json!(1));
+}
+
@@ -0,0 +1,111 @@
+//! Tests for lenient mode and the warnings it records.
+
+use std::collections::HashMap;
+use ubjson_rs::{DeserializerBuilder, UbjsonError, UbjsonType, UbjsonValue, Warning, WarningKind};
+
+fn lenient(bytes: &[u8]) -> (UbjsonValue, Vec<Warning>) {
+    DeserializerBuilder::new()
+        .lenient(true)
+        .value_from_slice_with_warnings(bytes)
+        .unwrap()
+}
+
+#[test]
+fn test_conformant_input_has_no_warnings() {
+    let (value, warnings) = lenient(b"[$i#U\x02\x01\x02");
+    assert_eq!(value.len(), Some(2));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_uncounted_typed_container_warning() {
+    let (value, warnings) = lenient(b"[$i\x01\x02]");
+    assert_eq!(value.len(), Some(2));
+    assert_eq!(warnings, vec![Warning { offset: 3, kind: WarningKind::UncountedTypedContainer }]);
+}
+
+#[test]
+fn test_non_ascii_char_warning() {
+    let bytes = "[CaCé]".as_bytes();
+    let (value, warnings) = lenient(bytes);
+    assert_eq!(value, UbjsonValue::Array(vec![UbjsonValue::Char('a'), UbjsonValue::Char('é')]));
+    assert_eq!(warnings, vec![Warning { offset: 4, kind: WarningKind::NonAsciiChar('é') }]);
+}
+
+#[test]
+fn test_noop_in_object() {
+    let bytes = b"{NU\x01aNZ}";
+    let err = DeserializerBuilder::new().value_from_slice(bytes).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidFormat(_)));
+
+    let (value, warnings) = lenient(bytes);
+    let mut expected = HashMap::new();
+    expected.insert("a".to_string(), UbjsonValue::Null);
+    assert_eq!(value, UbjsonValue::Object(expected));
+    assert_eq!(warnings, vec![
+        Warning { offset: 1, kind: WarningKind::NoOpInObject },
+        Warning { offset: 5, kind: WarningKind::NoOpInObject },
+    ]);
+}
+
+#[test]
+fn test_duplicate_key_keeps_last_value() {
+    let bytes = b"{U\x01aTU\x01aF}";
+    let err = DeserializerBuilder::new().value_from_slice(bytes).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidFormat(_)));
+
+    let (value, warnings) = lenient(bytes);
+    let mut expected = HashMap::new();
+    expected.insert("a".to_string(), UbjsonValue::Bool(false));
+    assert_eq!(value, UbjsonValue::Object(expected));
+    assert_eq!(warnings, vec![Warning { offset: 5, kind: WarningKind::DuplicateKey("a".to_string()) }]);
+}
+
+#[test]
+fn test_duplicate_key_in_typed_object() {
+    let bytes = b"{$i#U\x02U\x01a\x01U\x01a\x02";
+    let (value, warnings) = lenient(bytes);
+    match value {
+        UbjsonValue::StronglyTypedObject { pairs, .. } => assert_eq!(pairs["a"], UbjsonValue::Int8(2)),
+        other => panic!("expected a typed object, got {:?}", other),
+    }
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::DuplicateKey("a".to_string()));
+}
+
+#[test]
+fn test_high_precision_leading_plus_warning() {
+    let (value, warnings) = lenient(b"HU\x03+12");
+    assert_eq!(value, UbjsonValue::HighPrecision("+12".to_string()));
+    assert_eq!(warnings, vec![Warning { offset: 1, kind: WarningKind::HighPrecisionLeadingPlus }]);
+}
+
+#[test]
+fn test_warnings_from_reader_and_typed_elements() {
+    let bytes = "[$C#U\x02aπ".as_bytes();
+    let (value, warnings) = DeserializerBuilder::new()
+        .lenient(true)
+        .value_from_reader_with_warnings(bytes)
+        .unwrap();
+    assert!(matches!(value, UbjsonValue::StronglyTypedArray { element_type: UbjsonType::Char, .. }));
+    assert_eq!(warnings, vec![Warning { offset: 7, kind: WarningKind::NonAsciiChar('π') }]);
+}
+
+#[test]
+fn test_warnings_not_recorded_outside_lenient_mode() {
+    let (_, warnings) = DeserializerBuilder::new()
+        .value_from_slice_with_warnings(b"[$i\x01\x02]")
+        .unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_warnings_through_serde() {
+    let (map, warnings): (HashMap<String, bool>, _) = DeserializerBuilder::new()
+        .lenient(true)
+        .from_slice_with_warnings(b"{U\x01aTU\x01aF}")
+        .unwrap();
+    assert_eq!(map.get("a"), Some(&false));
+    assert_eq!(warnings.len(), 1);
+}
@@ -0,0 +1,151 @@
+//! Tests for resource budgets that span a whole decode.
+
+use std::collections::HashMap;
+use ubjson_rs::{value_to_vec, DeserializerBuilder, ErrorKind, UbjsonError, UbjsonType, UbjsonValue};
+
+fn strings_array(count: usize, len: usize) -> Vec<u8> {
+    let elements = (0..count).map(|_| UbjsonValue::String("x".repeat(len))).collect();
+    value_to_vec(&UbjsonValue::Array(elements)).unwrap()
+}
+
+#[test]
+fn test_budgets_allow_input_within_limits() {
+    let bytes = strings_array(4, 10);
+    let value = DeserializerBuilder::new()
+        .with_max_total_bytes(bytes.len())
+        .with_max_total_values(5)
+        .with_max_total_string_bytes(40)
+        .with_max_string_length(10)
+        .value_from_slice(&bytes)
+        .unwrap();
+    assert_eq!(value.len(), Some(4));
+}
+
+#[test]
+fn test_total_bytes_budget() {
+    let bytes = strings_array(4, 10);
+    let err = DeserializerBuilder::new()
+        .with_max_total_bytes(bytes.len() - 1)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::TotalBytesLimitExceeded(n) if n == bytes.len() - 1));
+    assert_eq!(err.classify(), ErrorKind::Limit);
+
+    let err = DeserializerBuilder::new()
+        .with_max_total_bytes(bytes.len() - 1)
+        .value_from_reader(bytes.as_slice())
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::TotalBytesLimitExceeded(_)));
+}
+
+#[test]
+fn test_total_values_budget() {
+    let bytes = strings_array(4, 1);
+    // The array itself plus four elements
+    let err = DeserializerBuilder::new()
+        .with_max_total_values(4)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::ValueCountLimitExceeded(4)));
+}
+
+#[test]
+fn test_total_values_budget_counts_payloadless_typed_elements() {
+    // [$Z#I 30000 declares thirty thousand nulls without any payload bytes
+    let bytes = [b'[', b'$', b'Z', b'#', b'I', 0x75, 0x30];
+    let err = DeserializerBuilder::new()
+        .with_max_total_values(1000)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::ValueCountLimitExceeded(1000)));
+}
+
+#[test]
+fn test_total_string_bytes_budget() {
+    let bytes = strings_array(4, 10);
+    let err = DeserializerBuilder::new()
+        .with_max_total_string_bytes(35)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::StringBytesLimitExceeded(35)));
+}
+
+#[test]
+fn test_string_length_applies_to_keys() {
+    let mut object = HashMap::new();
+    object.insert("a_rather_long_key".to_string(), UbjsonValue::Null);
+    let bytes = value_to_vec(&UbjsonValue::Object(object)).unwrap();
+    let err = DeserializerBuilder::new()
+        .with_max_string_length(8)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::StringLengthLimitExceeded(8)));
+}
+
+#[test]
+fn test_string_length_rejected_before_payload() {
+    // A header declaring a huge string with no payload behind it
+    let bytes = [b'S', b'l', 0x7F, 0xFF, 0xFF, 0xFF];
+    let err = DeserializerBuilder::new()
+        .with_max_string_length(1024)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::StringLengthLimitExceeded(1024)));
+}
+
+#[test]
+fn test_high_precision_counts_as_string() {
+    let bytes = value_to_vec(&UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::HighPrecision,
+        count: Some(2),
+        elements: vec![
+            UbjsonValue::HighPrecision("3.1415926535".to_string()),
+            UbjsonValue::HighPrecision("2.7182818284".to_string()),
+        ],
+    })
+    .unwrap();
+    let err = DeserializerBuilder::new()
+        .with_max_total_string_bytes(15)
+        .value_from_slice(&bytes)
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::StringBytesLimitExceeded(15)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_budgets_apply_to_serde_path() {
+    let bytes = ubjson_rs::to_vec(&vec!["abc".to_string(); 10]).unwrap();
+
+    let result: Result<Vec<String>, _> = DeserializerBuilder::new()
+        .with_max_total_values(5)
+        .from_slice(&bytes);
+    assert!(matches!(result, Err(UbjsonError::ValueCountLimitExceeded(5))));
+
+    let result: Result<Vec<String>, _> = DeserializerBuilder::new()
+        .with_max_total_string_bytes(20)
+        .from_reader(bytes.as_slice());
+    assert!(matches!(result, Err(UbjsonError::StringBytesLimitExceeded(20))));
+
+    let result: Result<Vec<String>, _> = DeserializerBuilder::new()
+        .with_max_string_length(2)
+        .from_slice(&bytes);
+    assert!(matches!(result, Err(UbjsonError::StringLengthLimitExceeded(2))));
+
+    let result: Result<Vec<String>, _> = DeserializerBuilder::new()
+        .with_max_total_bytes(16)
+        .from_slice(&bytes);
+    assert!(matches!(result, Err(UbjsonError::TotalBytesLimitExceeded(16))));
+}
+
+#[test]
+fn test_budgets_span_multiple_values() {
+    let mut bytes = value_to_vec(&UbjsonValue::String("hello".to_string())).unwrap();
+    bytes.extend(value_to_vec(&UbjsonValue::String("world".to_string())).unwrap());
+
+    let mut deserializer = DeserializerBuilder::new()
+        .with_max_total_string_bytes(8)
+        .build_slice(&bytes);
+    deserializer.deserialize_value().unwrap();
+    let err = deserializer.deserialize_value().unwrap_err();
+    assert!(matches!(err, UbjsonError::StringBytesLimitExceeded(8)));
+}
@@ -0,0 +1,242 @@
+//! Tests for JSON Merge Patch and JSON Patch on values.
+
+use std::collections::HashMap;
+use ubjson_rs::{value_from_slice, value_to_vec, Patch, PatchOperation, UbjsonError, UbjsonType, UbjsonValue};
+
+fn object(pairs: &[(&str, UbjsonValue)]) -> UbjsonValue {
+    UbjsonValue::Object(pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect())
+}
+
+fn string(s: &str) -> UbjsonValue {
+    UbjsonValue::String(s.to_string())
+}
+
+fn config() -> UbjsonValue {
+    let mut limits = HashMap::new();
+    limits.insert("cpu".to_string(), UbjsonValue::UInt8(2));
+    limits.insert("memory".to_string(), UbjsonValue::UInt8(64));
+    object(&[
+        ("name", string("svc")),
+        ("ports", UbjsonValue::strongly_typed_array(
+            UbjsonType::Int16,
+            vec![UbjsonValue::Int16(80), UbjsonValue::Int16(443)],
+        )),
+        ("limits", UbjsonValue::strongly_typed_object(UbjsonType::UInt8, limits)),
+    ])
+}
+
+#[test]
+fn test_merge_patch_rfc7396_examples() {
+    let mut value = object(&[
+        ("a", string("b")),
+        ("c", object(&[("d", string("e")), ("f", string("g"))])),
+    ]);
+    value.merge(&object(&[("a", string("z")), ("c", object(&[("f", UbjsonValue::Null)]))]));
+    assert_eq!(value, object(&[("a", string("z")), ("c", object(&[("d", string("e"))]))]));
+
+    let mut value = object(&[("a", UbjsonValue::Array(vec![string("b")]))]);
+    value.merge(&object(&[("a", string("c"))]));
+    assert_eq!(value, object(&[("a", string("c"))]));
+
+    let mut value = UbjsonValue::Array(vec![string("a")]);
+    value.merge(&object(&[("a", object(&[("bb", object(&[("ccc", UbjsonValue::Null)]))]))]));
+    assert_eq!(value, object(&[("a", object(&[("bb", object(&[]))]))]));
+
+    let mut value = object(&[("a", string("foo"))]);
+    value.merge(&UbjsonValue::Null);
+    assert_eq!(value, UbjsonValue::Null);
+}
+
+#[test]
+fn test_merge_keeps_typed_objects_when_compatible() {
+    let mut value = config();
+    value.merge(&object(&[("limits", object(&[("cpu", UbjsonValue::Int64(4)), ("memory", UbjsonValue::Null)]))]));
+    let limits = value.pointer("/limits").unwrap();
+    let mut expected = HashMap::new();
+    expected.insert("cpu".to_string(), UbjsonValue::UInt8(4));
+    assert_eq!(limits, &UbjsonValue::strongly_typed_object(UbjsonType::UInt8, expected));
+
+    value.merge(&object(&[("limits", object(&[("cpu", string("lots"))]))]));
+    assert!(matches!(value.pointer("/limits"), Some(UbjsonValue::Object(_))));
+    assert_eq!(value.pointer("/limits/cpu"), Some(&string("lots")));
+}
+
+#[test]
+fn test_json_patch_operations() {
+    let mut value = config();
+    let patch = Patch(vec![
+        PatchOperation::Test { path: "/ports/0".to_string(), value: UbjsonValue::Int64(80) },
+        PatchOperation::Add { path: "/ports/-".to_string(), value: UbjsonValue::Int32(8080) },
+        PatchOperation::Add { path: "/ports/0".to_string(), value: UbjsonValue::UInt8(22) },
+        PatchOperation::Replace { path: "/name".to_string(), value: string("api") },
+        PatchOperation::Copy { from: "/name".to_string(), path: "/alias".to_string() },
+        PatchOperation::Move { from: "/limits/cpu".to_string(), path: "/cpu".to_string() },
+        PatchOperation::Remove { path: "/ports/1".to_string() },
+    ]);
+    value.apply_patch(&patch).unwrap();
+
+    assert_eq!(
+        value.pointer("/ports").unwrap(),
+        &UbjsonValue::strongly_typed_array(
+            UbjsonType::Int16,
+            vec![UbjsonValue::Int16(22), UbjsonValue::Int16(443), UbjsonValue::Int16(8080)],
+        )
+    );
+    assert_eq!(value.pointer("/name"), Some(&string("api")));
+    assert_eq!(value.pointer("/alias"), Some(&string("api")));
+    assert_eq!(value.pointer("/cpu"), Some(&UbjsonValue::UInt8(2)));
+    assert_eq!(value.pointer("/limits").and_then(UbjsonValue::len), Some(1));
+
+    let bytes = value_to_vec(&value).unwrap();
+    assert_eq!(value_from_slice(&bytes).unwrap(), value);
+}
+
+#[test]
+fn test_incompatible_value_flattens_typed_array() {
+    let mut value = config();
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Replace { path: "/ports/1".to_string(), value: UbjsonValue::Int64(100_000) }]))
+        .unwrap();
+    assert_eq!(
+        value.pointer("/ports").unwrap(),
+        &UbjsonValue::Array(vec![UbjsonValue::Int16(80), UbjsonValue::Int64(100_000)])
+    );
+}
+
+#[test]
+fn test_integers_coerce_into_typed_float_arrays_when_exact() {
+    let mut value = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Float32,
+        count: Some(1),
+        elements: vec![UbjsonValue::Float32(0.5)],
+    };
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Add { path: "/-".to_string(), value: UbjsonValue::Int32(16_777_216) }]))
+        .unwrap();
+    assert_eq!(
+        value,
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Float32,
+            count: Some(2),
+            elements: vec![UbjsonValue::Float32(0.5), UbjsonValue::Float32(16_777_216.0)],
+        }
+    );
+
+    // 2^24 + 1 has no exact f32, so the array is flattened instead
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Add { path: "/-".to_string(), value: UbjsonValue::Int32(16_777_217) }]))
+        .unwrap();
+    assert_eq!(value.pointer("/2").unwrap(), &UbjsonValue::Int32(16_777_217));
+    assert!(matches!(value, UbjsonValue::Array(_)));
+
+    let mut value = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Float64,
+        count: None,
+        elements: vec![],
+    };
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Add { path: "/0".to_string(), value: UbjsonValue::Int64(-42) }]))
+        .unwrap();
+    assert_eq!(
+        value,
+        UbjsonValue::StronglyTypedArray { element_type: UbjsonType::Float64, count: None, elements: vec![UbjsonValue::Float64(-42.0)] }
+    );
+}
+
+#[test]
+fn test_values_widen_into_typed_containers() {
+    let mut value = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Float64,
+        count: Some(1),
+        elements: vec![UbjsonValue::Float64(0.1)],
+    };
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Add { path: "/-".to_string(), value: UbjsonValue::Float32(1.5) }]))
+        .unwrap();
+    assert_eq!(
+        value,
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Float64,
+            count: Some(2),
+            elements: vec![UbjsonValue::Float64(0.1), UbjsonValue::Float64(1.5)],
+        }
+    );
+
+    let mut value = UbjsonValue::strongly_typed_object(UbjsonType::Int64, HashMap::new());
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Add { path: "/n".to_string(), value: UbjsonValue::Int8(-3) }]))
+        .unwrap();
+    assert_eq!(
+        value,
+        UbjsonValue::strongly_typed_object(UbjsonType::Int64, HashMap::from([("n".to_string(), UbjsonValue::Int64(-3))]))
+    );
+}
+
+#[test]
+fn test_non_ascii_char_flattens_typed_char_container() {
+    let mut value = UbjsonValue::StronglyTypedObject {
+        value_type: UbjsonType::Char,
+        count: Some(1),
+        pairs: HashMap::from([("a".to_string(), UbjsonValue::Char('a'))]),
+    };
+    value
+        .apply_patch(&Patch(vec![PatchOperation::Add { path: "/b".to_string(), value: UbjsonValue::Char('é') }]))
+        .unwrap();
+    assert_eq!(
+        value,
+        object(&[("a", UbjsonValue::Char('a')), ("b", UbjsonValue::Char('é'))])
+    );
+}
+
+#[test]
+fn test_failed_patch_leaves_value_unchanged() {
+    let mut value = config();
+    let patch = Patch(vec![
+        PatchOperation::Remove { path: "/name".to_string() },
+        PatchOperation::Test { path: "/ports/1".to_string(), value: UbjsonValue::Int16(444) },
+    ]);
+    let err = value.apply_patch(&patch).unwrap_err();
+    assert!(matches!(err, UbjsonError::PatchFailed { index: 1, .. }), "{:?}", err);
+    assert_eq!(value, config());
+
+    for operation in [
+        PatchOperation::Remove { path: "/missing".to_string() },
+        PatchOperation::Add { path: "/ports/5".to_string(), value: UbjsonValue::Null },
+        PatchOperation::Add { path: "/ports/01".to_string(), value: UbjsonValue::Null },
+        PatchOperation::Replace { path: "/nope".to_string(), value: UbjsonValue::Null },
+        PatchOperation::Move { from: "/limits".to_string(), path: "/limits/inner".to_string() },
+        PatchOperation::Add { path: "no-slash".to_string(), value: UbjsonValue::Null },
+        PatchOperation::Add { path: "/name/x".to_string(), value: UbjsonValue::Null },
+    ] {
+        let result = value.apply_patch(&Patch(vec![operation.clone()]));
+        assert!(matches!(result, Err(UbjsonError::PatchFailed { index: 0, .. })), "{:?}", operation);
+    }
+}
+
+#[test]
+fn test_pointer_escapes() {
+    let mut value = object(&[("a/b", object(&[("m~n", UbjsonValue::Int8(1))]))]);
+    assert_eq!(value.pointer("/a~1b/m~0n"), Some(&UbjsonValue::Int8(1)));
+    assert_eq!(value.pointer(""), Some(&value.clone()));
+    *value.pointer_mut("/a~1b/m~0n").unwrap() = UbjsonValue::Int8(2);
+    assert_eq!(value.pointer("/a~1b/m~0n"), Some(&UbjsonValue::Int8(2)));
+    assert_eq!(value.pointer("/a~2b"), None);
+}
+
+#[test]
+fn test_patch_as_value() {
+    let patch = Patch(vec![
+        PatchOperation::Add { path: "/a".to_string(), value: UbjsonValue::Int8(1) },
+        PatchOperation::Move { from: "/a".to_string(), path: "/b".to_string() },
+        PatchOperation::Remove { path: "/b".to_string() },
+    ]);
+    let value = UbjsonValue::from(&patch);
+    let bytes = value_to_vec(&value).unwrap();
+    let decoded = Patch::try_from(&value_from_slice(&bytes).unwrap()).unwrap();
+    assert_eq!(decoded, patch);
+
+    let bad = UbjsonValue::Array(vec![object(&[("op", string("jump")), ("path", string("/a"))])]);
+    assert!(matches!(Patch::try_from(&bad), Err(UbjsonError::InvalidPatch(_))));
+    let bad = UbjsonValue::Array(vec![object(&[("op", string("add")), ("path", string("/a"))])]);
+    assert!(matches!(Patch::try_from(&bad), Err(UbjsonError::InvalidPatch(_))));
+}
@@ -2,8 +2,8 @@
 
 use ubjson_rs::{
     UbjsonValue, UbjsonError, SerializerBuilder, DeserializerBuilder,
-    to_vec, to_writer, from_slice, from_reader,
-    value_to_vec, value_to_writer, value_from_slice, value_from_reader,
+    to_vec, to_writer, from_slice, from_slice_partial, from_reader,
+    value_to_vec, value_to_writer, value_from_slice, value_from_slice_partial, value_from_reader,
 };
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
@@ -136,6 +136,7 @@ fn test_to_writer_and_from_reader() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_value_to_vec_and_value_from_slice_primitives() {
     let test_cases = vec![
         UbjsonValue::Null,
@@ -158,9 +159,15 @@ fn test_value_to_vec_and_value_from_slice_primitives() {
         let bytes = value_to_vec(&value).unwrap();
         assert!(!bytes.is_empty());
         
-        // Deserialize back
+        // Deserialize back; a non-ASCII char comes back as the string it was written as
         let deserialized = value_from_slice(&bytes).unwrap();
-        assert_eq!(deserialized, value);
+        match value {
+            UbjsonValue::Char(c) if !c.is_ascii() => {
+                assert_eq!(bytes[0], b'S');
+                assert_eq!(deserialized, UbjsonValue::String(c.to_string()));
+            }
+            value => assert_eq!(deserialized, value),
+        }
     }
 }
 
@@ -473,4 +480,64 @@ fn test_error_handling_truncated_data() {
     // Should fail to deserialize
     let result: Result<String, UbjsonError> = from_slice(&bytes);
     assert!(result.is_err());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_from_slice_rejects_trailing_data() {
+    let mut bytes = to_vec(&42i32).unwrap();
+    let value_len = bytes.len();
+    bytes.push(b'Z');
+
+    let result: Result<i32, UbjsonError> = from_slice(&bytes);
+    assert!(matches!(result, Err(UbjsonError::TrailingData { offset }) if offset == value_len));
+
+    let result = value_from_slice(&bytes);
+    assert!(matches!(result, Err(UbjsonError::TrailingData { offset }) if offset == value_len));
+
+    // Readers are streams, so bytes after the value are left for the next read
+    let result: i32 = from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(result, 42);
+}
+
+#[test]
+fn test_builder_allows_trailing_data() {
+    let mut bytes = to_vec(&"first").unwrap();
+    bytes.extend(to_vec(&"second").unwrap());
+
+    let builder = DeserializerBuilder::new().with_allow_trailing_data(true);
+    let result: String = builder.clone().from_slice(&bytes).unwrap();
+    assert_eq!(result, "first");
+    assert_eq!(
+        builder.value_from_slice(&bytes).unwrap(),
+        UbjsonValue::String("first".to_string())
+    );
+
+    let result: Result<String, UbjsonError> = DeserializerBuilder::new().from_slice(&bytes);
+    assert!(matches!(result, Err(UbjsonError::TrailingData { .. })));
+}
+
+#[test]
+fn test_from_slice_partial_walks_buffer() {
+    let first = TestStruct {
+        name: "Alice".to_string(),
+        age: 30,
+        active: true,
+    };
+    let mut bytes = to_vec(&first).unwrap();
+    let first_len = bytes.len();
+    bytes.extend(to_vec(&7u8).unwrap());
+    bytes.extend(to_vec(&vec!["a".to_string(), "b".to_string()]).unwrap());
+
+    let (value, used): (TestStruct, usize) = from_slice_partial(&bytes).unwrap();
+    assert_eq!(value, first);
+    assert_eq!(used, first_len);
+
+    let rest = &bytes[used..];
+    let (value, used): (u8, usize) = from_slice_partial(rest).unwrap();
+    assert_eq!(value, 7);
+
+    let rest = &rest[used..];
+    let (value, used) = value_from_slice_partial(rest).unwrap();
+    assert_eq!(value.len(), Some(2));
+    assert_eq!(used, rest.len());
+}
@@ -0,0 +1,126 @@
+//! Tests for JSONPath-style queries and the `ubjson query` command.
+
+use std::collections::HashMap;
+use std::process::Command;
+use ubjson_rs::{value_to_vec, ErrorKind, PathSegment, Query, UbjsonError, UbjsonType, UbjsonValue};
+
+fn object(pairs: Vec<(&str, UbjsonValue)>) -> UbjsonValue {
+    UbjsonValue::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<HashMap<_, _>>())
+}
+
+fn player(port: UbjsonValue, damage: f32) -> UbjsonValue {
+    object(vec![
+        ("port", port),
+        ("stats", object(vec![("damage", UbjsonValue::Float32(damage))])),
+    ])
+}
+
+fn game() -> UbjsonValue {
+    object(vec![
+        ("stage", UbjsonValue::String("battlefield".to_string())),
+        ("players", UbjsonValue::Array(vec![
+            player(UbjsonValue::Int8(1), 10.5),
+            player(UbjsonValue::UInt8(2), 42.0),
+            player(UbjsonValue::Int32(3), 7.0),
+        ])),
+        ("frames", UbjsonValue::strongly_typed_array(
+            UbjsonType::Int16,
+            (0..6).map(UbjsonValue::Int16).collect(),
+        )),
+    ])
+}
+
+fn select(expr: &str) -> Vec<UbjsonValue> {
+    game().query(expr).unwrap().into_iter().cloned().collect()
+}
+
+#[test]
+fn test_child_access_and_wildcards() {
+    assert_eq!(select("$.stage"), vec![UbjsonValue::String("battlefield".to_string())]);
+    assert_eq!(select("$['stage']"), select("stage"));
+    assert_eq!(select("$.players[1].port"), vec![UbjsonValue::UInt8(2)]);
+    assert_eq!(select("players[-1].port"), vec![UbjsonValue::Int32(3)]);
+    assert_eq!(select("$.players[*].port"), vec![UbjsonValue::Int8(1), UbjsonValue::UInt8(2), UbjsonValue::Int32(3)]);
+    assert_eq!(select("$.players[0].*").len(), 2);
+    assert!(select("$.missing").is_empty());
+    assert!(select("$.players[9]").is_empty());
+    assert!(select("$.stage[0]").is_empty());
+}
+
+#[test]
+fn test_slices_and_unions_over_typed_arrays() {
+    let ints = |v: &[i16]| v.iter().map(|&n| UbjsonValue::Int16(n)).collect::<Vec<_>>();
+    assert_eq!(select("$.frames[1:3]"), ints(&[1, 2]));
+    assert_eq!(select("$.frames[::2]"), ints(&[0, 2, 4]));
+    assert_eq!(select("$.frames[-2:]"), ints(&[4, 5]));
+    assert_eq!(select("$.frames[::-2]"), ints(&[5, 3, 1]));
+    assert_eq!(select("$.frames[0, -1]"), ints(&[0, 5]));
+    assert!(select("$.frames[3:1]").is_empty());
+    assert!(select("$.frames[::0]").is_empty());
+}
+
+#[test]
+fn test_recursive_descent() {
+    assert_eq!(
+        select("$..damage"),
+        vec![UbjsonValue::Float32(10.5), UbjsonValue::Float32(42.0), UbjsonValue::Float32(7.0)]
+    );
+    assert_eq!(select("$..port").len(), 3);
+    // The root, three players and the stats of each, plus the frames array and its elements
+    assert_eq!(game().query("$..*").unwrap().len(), 1 + 1 + 3 + 3 * 2 + 3 + 1 + 6);
+}
+
+#[test]
+fn test_filters() {
+    assert_eq!(select("$.players[?(@.port == 2)].stats.damage"), vec![UbjsonValue::Float32(42.0)]);
+    assert_eq!(select("$.players[?@.port >= 2 && @.stats.damage < 10].port"), vec![UbjsonValue::Int32(3)]);
+    assert_eq!(select("$.players[?(@.port == 1 || !(@.port < 3))].port").len(), 2);
+    assert_eq!(select("$.players[?(@.stats)]").len(), 3);
+    assert!(select("$.players[?(@.missing)]").is_empty());
+    assert!(select("$.players[?(@.port < 'a')]").is_empty());
+    assert_eq!(select("$.players[?(@.port != 2)]").len(), 2);
+    assert_eq!(select("$.frames[?(@ > 3)]"), vec![UbjsonValue::Int16(4), UbjsonValue::Int16(5)]);
+    assert_eq!(select("$..[?(@.damage == 7.0)].damage"), vec![UbjsonValue::Float32(7.0)]);
+    assert_eq!(select("$.players[?(@.port == $.players[2].port)]").len(), 1);
+    assert_eq!(select("$[?(@ == \"battlefield\")]").len(), 1);
+}
+
+#[test]
+fn test_select_paths() {
+    let value = game();
+    let query: Query = "$.players[?(@.port > 1)].port".parse().unwrap();
+    let paths: Vec<_> = query.select_paths(&value).into_iter().map(|(path, _)| path).collect();
+    let expected = |i| vec![PathSegment::Key("players".to_string()), PathSegment::Index(i), PathSegment::Key("port".to_string())];
+    assert_eq!(paths, vec![expected(1), expected(2)]);
+}
+
+#[test]
+fn test_invalid_queries() {
+    for (expr, position) in [("$.", 2), ("$[1", 3), ("$['a]", 5), ("$[?(@.a == )]", 11), ("$[?(1)]", 4), ("$x", 1)] {
+        let error = Query::parse(expr).unwrap_err();
+        assert!(
+            matches!(error, UbjsonError::InvalidQuery { position: p, .. } if p == position),
+            "{}: {:?}", expr, error
+        );
+        assert_eq!(error.classify(), ErrorKind::Data);
+    }
+}
+
+#[test]
+fn test_query_command() {
+    let path = std::env::temp_dir().join(format!("ubjson-query-{}.ubj", std::process::id()));
+    std::fs::write(&path, value_to_vec(&game()).unwrap()).unwrap();
+    let run = |expr: &str| {
+        Command::new(env!("CARGO_BIN_EXE_ubjson"))
+            .args(["query", expr, path.to_str().unwrap()])
+            .output()
+            .unwrap()
+    };
+
+    let output = run("$.players[?(@.port == 2)].stats.damage");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "42\n");
+    assert_eq!(run("$.missing").status.code(), Some(1));
+    assert_eq!(run("$[").status.code(), Some(2));
+    std::fs::remove_file(&path).unwrap();
+}
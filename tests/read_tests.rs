@@ -0,0 +1,193 @@
+use std::io;
+use ubjson_rs::read::{IoRead, Read, Reference, SliceRead};
+use ubjson_rs::{UbjsonDeserializer, UbjsonValue};
+
+/// Reader that hands out at most one byte per call.
+struct OneByteReader<'a> {
+    data: &'a [u8],
+}
+
+impl io::Read for OneByteReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.data.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.data[0];
+        self.data = &self.data[1..];
+        Ok(1)
+    }
+}
+
+/// Reader that counts how many times the deserializer asks for data.
+struct CountingReader<R> {
+    inner: R,
+    calls: usize,
+}
+
+impl<R: io::Read> io::Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.calls += 1;
+        self.inner.read(buf)
+    }
+}
+
+#[test]
+fn test_slice_read_peek_does_not_consume() {
+    let mut read = SliceRead::new(b"ab");
+    assert_eq!(read.peek().unwrap(), Some(b'a'));
+    assert_eq!(read.peek().unwrap(), Some(b'a'));
+    assert_eq!(read.position(), 0);
+    assert_eq!(read.next().unwrap(), Some(b'a'));
+    assert_eq!(read.next().unwrap(), Some(b'b'));
+    assert_eq!(read.peek().unwrap(), None);
+    assert_eq!(read.next().unwrap(), None);
+    assert_eq!(read.position(), 2);
+}
+
+#[test]
+fn test_slice_read_slice_is_borrowed() {
+    let data = b"hello world";
+    let mut read = SliceRead::new(data);
+    let mut scratch = Vec::new();
+    match read.read_slice(5, &mut scratch).unwrap() {
+        Reference::Borrowed(bytes) => assert_eq!(bytes, b"hello"),
+        Reference::Copied(_) => panic!("slice input should not copy"),
+    }
+    assert!(scratch.is_empty());
+    assert_eq!(read.remaining(), b" world");
+}
+
+#[test]
+fn test_slice_read_slice_past_end_fails() {
+    let mut read = SliceRead::new(b"abc");
+    let mut scratch = Vec::new();
+    assert!(read.read_slice(4, &mut scratch).is_err());
+}
+
+#[test]
+fn test_io_read_across_buffer_boundaries() {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+    let mut read = IoRead::new(data.as_slice());
+    let mut scratch = Vec::new();
+
+    assert_eq!(read.next().unwrap(), Some(data[0]));
+    let bytes = read.read_slice(10_000, &mut scratch).unwrap();
+    assert_eq!(&*bytes, &data[1..10_001]);
+    read.skip(5_000).unwrap();
+    assert_eq!(read.position(), 15_001);
+    assert_eq!(read.peek().unwrap(), Some(data[15_001]));
+
+    let mut rest = vec![0u8; data.len() - 15_001];
+    read.read_exact(&mut rest).unwrap();
+    assert_eq!(rest, &data[15_001..]);
+    assert_eq!(read.peek().unwrap(), None);
+}
+
+#[test]
+fn test_io_read_handles_short_reads() {
+    let bytes = ubjson_rs::value_to_vec(&UbjsonValue::String("short reads".to_string())).unwrap();
+    let reader = OneByteReader { data: &bytes };
+    let mut deserializer = UbjsonDeserializer::new(reader);
+    assert_eq!(
+        deserializer.deserialize_value().unwrap(),
+        UbjsonValue::String("short reads".to_string())
+    );
+}
+
+#[test]
+fn test_io_read_buffers_underlying_reader() {
+    let value = UbjsonValue::Array((0..1000).map(UbjsonValue::Int32).collect());
+    let bytes = ubjson_rs::value_to_vec(&value).unwrap();
+    let mut reader = CountingReader { inner: bytes.as_slice(), calls: 0 };
+
+    let mut deserializer = UbjsonDeserializer::new(&mut reader);
+    assert_eq!(deserializer.deserialize_value().unwrap(), value);
+    drop(deserializer);
+
+    // 5000 bytes of input should be served by a handful of reads, not one per byte
+    assert!(reader.calls < 10, "made {} reads", reader.calls);
+}
+
+#[test]
+fn test_position_tracks_consumed_bytes() {
+    let mut bytes = ubjson_rs::value_to_vec(&UbjsonValue::Int16(300)).unwrap();
+    let first_len = bytes.len();
+    bytes.extend(ubjson_rs::value_to_vec(&UbjsonValue::Bool(true)).unwrap());
+
+    let mut deserializer = UbjsonDeserializer::from_slice(&bytes);
+    assert_eq!(deserializer.deserialize_value().unwrap(), UbjsonValue::Int16(300));
+    assert_eq!(deserializer.position(), first_len);
+    assert_eq!(deserializer.deserialize_value().unwrap(), UbjsonValue::Bool(true));
+    assert_eq!(deserializer.position(), bytes.len());
+
+    let mut deserializer = UbjsonDeserializer::new(bytes.as_slice());
+    deserializer.deserialize_value().unwrap();
+    assert_eq!(deserializer.position(), first_len);
+}
+
+#[test]
+fn test_slice_and_reader_agree() {
+    let mut object = std::collections::HashMap::new();
+    object.insert("name".to_string(), UbjsonValue::String("ubjson".to_string()));
+    object.insert("tags".to_string(), UbjsonValue::StronglyTypedArray {
+        element_type: ubjson_rs::UbjsonType::UInt8,
+        count: Some(3),
+        elements: vec![UbjsonValue::UInt8(1), UbjsonValue::UInt8(2), UbjsonValue::UInt8(3)],
+    });
+    let value = UbjsonValue::Object(object);
+    let bytes = ubjson_rs::value_to_vec(&value).unwrap();
+
+    let from_slice = UbjsonDeserializer::from_slice(&bytes).deserialize_value().unwrap();
+    let from_reader = UbjsonDeserializer::new(bytes.as_slice()).deserialize_value().unwrap();
+
+    assert_eq!(from_slice, value);
+    assert_eq!(from_reader, value);
+}
+
+#[test]
+fn test_io_read_decodes_values_in_sequence() {
+    let mut bytes = ubjson_rs::value_to_vec(&UbjsonValue::String("first".to_string())).unwrap();
+    bytes.extend(ubjson_rs::value_to_vec(&UbjsonValue::Int32(2)).unwrap());
+    bytes.extend(ubjson_rs::value_to_vec(&UbjsonValue::Bool(true)).unwrap());
+
+    // One deserializer keeps its read-ahead between values
+    let mut deserializer = UbjsonDeserializer::new(bytes.as_slice());
+    assert_eq!(deserializer.deserialize_value().unwrap(), UbjsonValue::String("first".to_string()));
+    assert_eq!(deserializer.deserialize_value().unwrap(), UbjsonValue::Int32(2));
+
+    // Unwrapping it hands back the bytes read ahead along with the reader
+    let (rest, unread) = deserializer.into_inner();
+    assert_eq!(unread, b"T");
+    assert!(rest.is_empty());
+
+    let mut stream = bytes.as_slice();
+    let mut deserializer = UbjsonDeserializer::new(&mut stream);
+    assert_eq!(deserializer.deserialize_value().unwrap(), UbjsonValue::String("first".to_string()));
+    let (_, unread) = deserializer.into_inner();
+    let mut rest = io::Read::chain(unread.as_slice(), stream);
+    assert_eq!(ubjson_rs::value_from_reader(&mut rest).unwrap(), UbjsonValue::Int32(2));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_str_borrows_from_slice_input() {
+    use serde::Deserialize;
+
+    let bytes = ubjson_rs::value_to_vec(&UbjsonValue::String("borrowed".to_string())).unwrap();
+    let mut deserializer = UbjsonDeserializer::from_slice(&bytes);
+    let borrowed = <&str>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(borrowed, "borrowed");
+    assert!(std::ptr::eq(borrowed.as_ptr(), bytes[3..].as_ptr()));
+
+    // Reader input can only hand out copies
+    let mut deserializer = UbjsonDeserializer::new(bytes.as_slice());
+    assert!(<&str>::deserialize(&mut deserializer).is_err());
+    let mut deserializer = UbjsonDeserializer::new(bytes.as_slice());
+    assert_eq!(String::deserialize(&mut deserializer).unwrap(), "borrowed");
+
+    // Leading no-ops are skipped
+    let padded = [b"NN".as_slice(), &bytes].concat();
+    let mut deserializer = UbjsonDeserializer::from_slice(&padded);
+    assert_eq!(<&str>::deserialize(&mut deserializer).unwrap(), "borrowed");
+    deserializer.end().unwrap();
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use ubjson_rs::{value_to_vec, DeserializerBuilder, UbjsonError, UbjsonType, UbjsonValue};
+
+fn sample_document() -> UbjsonValue {
+    let mut object = HashMap::new();
+    object.insert("id".to_string(), UbjsonValue::Int32(7));
+    object.insert(
+        "readings".to_string(),
+        UbjsonValue::Array(vec![
+            UbjsonValue::Int16(100),
+            UbjsonValue::Int16(200),
+            UbjsonValue::Int16(300),
+        ]),
+    );
+    UbjsonValue::Object(object)
+}
+
+#[test]
+fn test_recover_clean_input() {
+    let mut bytes = value_to_vec(&sample_document()).unwrap();
+    bytes.extend(value_to_vec(&UbjsonValue::Bool(true)).unwrap());
+
+    let document = DeserializerBuilder::new().recover_from_slice(&bytes);
+    assert!(document.is_complete());
+    assert_eq!(document.values, vec![sample_document(), UbjsonValue::Bool(true)]);
+}
+
+#[test]
+fn test_recover_truncated_array() {
+    let value = UbjsonValue::Array(vec![
+        UbjsonValue::Int32(1),
+        UbjsonValue::Int32(2),
+        UbjsonValue::String("three".to_string()),
+    ]);
+    let bytes = value_to_vec(&value).unwrap();
+    // Cut the input in the middle of the string
+    let truncated = &bytes[..bytes.len() - 3];
+
+    let document = DeserializerBuilder::new().recover_from_slice(truncated);
+    assert_eq!(document.values, vec![UbjsonValue::Array(vec![
+        UbjsonValue::Int32(1),
+        UbjsonValue::Int32(2),
+    ])]);
+    assert_eq!(document.errors.len(), 1);
+    assert_eq!(document.errors[0].offset, truncated.len());
+}
+
+#[test]
+fn test_recover_truncated_nested_containers() {
+    let mut object = HashMap::new();
+    object.insert(
+        "readings".to_string(),
+        UbjsonValue::Array(vec![UbjsonValue::Int16(100), UbjsonValue::Int16(200), UbjsonValue::Int16(300)]),
+    );
+    let value = UbjsonValue::Array(vec![UbjsonValue::Bool(true), UbjsonValue::Object(object)]);
+    let bytes = value_to_vec(&value).unwrap();
+    // Drop the final Int16 payload byte and all three end markers
+    let truncated = &bytes[..bytes.len() - 4];
+
+    let document = DeserializerBuilder::new().recover_from_slice(truncated);
+    assert_eq!(document.errors.len(), 1);
+
+    let mut expected = HashMap::new();
+    expected.insert(
+        "readings".to_string(),
+        UbjsonValue::Array(vec![UbjsonValue::Int16(100), UbjsonValue::Int16(200)]),
+    );
+    assert_eq!(document.values, vec![UbjsonValue::Array(vec![
+        UbjsonValue::Bool(true),
+        UbjsonValue::Object(expected),
+    ])]);
+}
+
+#[test]
+fn test_recover_truncated_typed_array() {
+    let value = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Int32,
+        count: Some(4),
+        elements: (1..=4).map(UbjsonValue::Int32).collect(),
+    };
+    let bytes = value_to_vec(&value).unwrap();
+    let truncated = &bytes[..bytes.len() - 6];
+
+    let document = DeserializerBuilder::new().recover_from_slice(truncated);
+    assert_eq!(document.values, vec![UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Int32,
+        count: Some(2),
+        elements: vec![UbjsonValue::Int32(1), UbjsonValue::Int32(2)],
+    }]);
+    assert_eq!(document.errors.len(), 1);
+}
+
+#[test]
+fn test_recover_resynchronizes_after_corruption() {
+    let mut object = HashMap::new();
+    object.insert("id".to_string(), UbjsonValue::Int32(7));
+    let first = value_to_vec(&UbjsonValue::Object(object)).unwrap();
+    let second = value_to_vec(&UbjsonValue::Array(vec![UbjsonValue::Null])).unwrap();
+
+    let mut bytes = first.clone();
+    // Flip the marker of the "id" value into an invalid one
+    let id_marker = first.iter().position(|&b| b == b'l').unwrap();
+    bytes[id_marker] = 0xFF;
+    bytes.extend(&second);
+
+    let document = DeserializerBuilder::new().recover_from_slice(&bytes);
+    assert_eq!(document.errors.len(), 1);
+    assert_eq!(document.errors[0].offset, id_marker + 1);
+    assert!(matches!(document.errors[0].error, UbjsonError::InvalidTypeMarker(0xFF)));
+    assert_eq!(document.values, vec![
+        UbjsonValue::Object(HashMap::new()),
+        UbjsonValue::Array(vec![UbjsonValue::Null]),
+    ]);
+}
+
+#[test]
+fn test_recover_from_reader_matches_slice() {
+    let bytes = value_to_vec(&sample_document()).unwrap();
+    let truncated = &bytes[..bytes.len() - 3];
+
+    let from_slice = DeserializerBuilder::new().recover_from_slice(truncated);
+    let from_reader = DeserializerBuilder::new().recover_from_reader(truncated);
+    assert_eq!(from_slice.values, from_reader.values);
+    assert_eq!(from_slice.errors.len(), from_reader.errors.len());
+    assert_eq!(from_slice.errors[0].offset, from_reader.errors[0].offset);
+}
+
+#[test]
+fn test_recover_respects_limits() {
+    let nested = b"[[[[[Z]]]]]";
+    let document = DeserializerBuilder::new()
+        .with_max_depth(3)
+        .recover_from_slice(nested);
+    assert!(matches!(document.errors[0].error, UbjsonError::DepthLimitExceeded(3)));
+    assert!(!document.is_complete());
+}
+
+#[test]
+fn test_recover_charges_skipped_bytes_to_the_budget() {
+    // Garbage to skip, then two values past the end of the budget
+    let mut bytes = vec![0xFF; 16];
+    bytes.extend(value_to_vec(&UbjsonValue::Array(vec![UbjsonValue::Null])).unwrap());
+    bytes.extend(value_to_vec(&UbjsonValue::Array(vec![UbjsonValue::Null])).unwrap());
+
+    let document = DeserializerBuilder::new()
+        .with_max_total_bytes(8)
+        .recover_from_slice(&bytes);
+    assert!(document.values.is_empty());
+    // Recovery stops at the budget instead of reporting it again for each value
+    assert_eq!(document.errors.len(), 2);
+    assert!(matches!(document.errors[0].error, UbjsonError::InvalidTypeMarker(0xFF)));
+    assert!(matches!(document.errors[1].error, UbjsonError::TotalBytesLimitExceeded(8)));
+    assert_eq!(document.errors[1].offset, 8);
+}
+
+#[test]
+fn test_recover_damaged_typed_array_header() {
+    // The element type is intact but the count is not
+    let mut bytes = b"[$l#\xFF".to_vec();
+    bytes.extend(value_to_vec(&UbjsonValue::Array(vec![UbjsonValue::Null])).unwrap());
+
+    let document = DeserializerBuilder::new().recover_from_slice(&bytes);
+    assert_eq!(document.values, vec![
+        UbjsonValue::StronglyTypedArray { element_type: UbjsonType::Int32, count: None, elements: Vec::new() },
+        UbjsonValue::Array(vec![UbjsonValue::Null]),
+    ]);
+    assert_eq!(document.errors.len(), 1);
+
+    // Without an element type there is nothing typed to keep
+    let document = DeserializerBuilder::new().recover_from_slice(b"[$\xFF#U\x01");
+    assert_eq!(document.values, vec![UbjsonValue::Array(Vec::new())]);
+}
+
+#[test]
+fn test_recover_reports_fragments_of_damaged_containers() {
+    // An outer array whose second element is damaged, followed by an intact value
+    let mut bytes = b"[[i\x01]\xFF[i\x02]]".to_vec();
+    bytes.extend(value_to_vec(&UbjsonValue::Array(vec![UbjsonValue::Null])).unwrap());
+
+    let document = DeserializerBuilder::new().recover_from_slice(&bytes);
+    assert_eq!(document.values, vec![
+        UbjsonValue::Array(vec![UbjsonValue::Array(vec![UbjsonValue::Int8(1)])]),
+        UbjsonValue::Array(vec![UbjsonValue::Null]),
+    ]);
+    assert_eq!(document.errors.len(), 2);
+    assert!(matches!(document.errors[0].error, UbjsonError::InvalidTypeMarker(0xFF)));
+    // The inner array after the damage is reported where it starts
+    assert_eq!(document.errors[1].offset, 6);
+    assert!(matches!(document.errors[1].error, UbjsonError::InvalidFormat(_)));
+}
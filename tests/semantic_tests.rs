@@ -0,0 +1,150 @@
+//! Tests for semantic comparison of values.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use ubjson_rs::{SemanticValue, UbjsonType, UbjsonValue};
+
+fn object(pairs: &[(&str, UbjsonValue)]) -> HashMap<String, UbjsonValue> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+}
+
+#[test]
+fn test_numbers_equal_across_widths() {
+    let ones = [
+        UbjsonValue::Int8(1),
+        UbjsonValue::UInt8(1),
+        UbjsonValue::Int16(1),
+        UbjsonValue::Int32(1),
+        UbjsonValue::Int64(1),
+        UbjsonValue::Float32(1.0),
+        UbjsonValue::Float64(1.0),
+        UbjsonValue::HighPrecision("1".to_string()),
+        UbjsonValue::HighPrecision("1.0e0".to_string()),
+    ];
+    for a in &ones {
+        for b in &ones {
+            assert!(a.semantic_eq(b), "{:?} != {:?}", a, b);
+        }
+    }
+    assert!(UbjsonValue::Float64(-0.0).semantic_eq(&UbjsonValue::Int8(0)));
+    assert!(!UbjsonValue::Float32(0.1).semantic_eq(&UbjsonValue::Float64(0.1)));
+    assert!(UbjsonValue::Float32(0.5).semantic_eq(&UbjsonValue::Float64(0.5)));
+    assert!(!UbjsonValue::Int8(1).semantic_eq(&UbjsonValue::Bool(true)));
+    assert!(!UbjsonValue::Int8(1).semantic_eq(&UbjsonValue::String("1".to_string())));
+}
+
+#[test]
+fn test_char_and_containers_ignore_encoding() {
+    assert!(UbjsonValue::Char('a').semantic_eq(&UbjsonValue::String("a".to_string())));
+
+    let plain = UbjsonValue::Array(vec![UbjsonValue::Int32(1), UbjsonValue::Int32(2)]);
+    let typed = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Int8,
+        count: None,
+        elements: vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2)],
+    };
+    assert_ne!(plain, typed);
+    assert!(plain.semantic_eq(&typed));
+
+    let plain = UbjsonValue::Object(object(&[("a", UbjsonValue::Int64(300)), ("b", UbjsonValue::Null)]));
+    let typed = UbjsonValue::strongly_typed_object(
+        UbjsonType::Int16,
+        object(&[("a", UbjsonValue::Int16(300)), ("b", UbjsonValue::Null)]),
+    );
+    assert!(plain.semantic_eq(&typed));
+    let other = UbjsonValue::Object(object(&[("a", UbjsonValue::Int64(301)), ("b", UbjsonValue::Null)]));
+    assert!(!plain.semantic_eq(&other));
+}
+
+#[test]
+fn test_total_ordering() {
+    let mut values = vec![
+        UbjsonValue::Object(HashMap::new()),
+        UbjsonValue::Array(vec![]),
+        UbjsonValue::String("a".to_string()),
+        UbjsonValue::HighPrecision("not a number".to_string()),
+        UbjsonValue::Float64(f64::NAN),
+        UbjsonValue::Float64(f64::INFINITY),
+        UbjsonValue::Int64(i64::MAX),
+        UbjsonValue::Float64(2.5),
+        UbjsonValue::Int8(2),
+        UbjsonValue::Float32(f32::NEG_INFINITY),
+        UbjsonValue::Bool(true),
+        UbjsonValue::Bool(false),
+        UbjsonValue::Null,
+    ];
+    values.reverse();
+    values.sort_by(|a, b| a.semantic_cmp(b));
+    assert_eq!(values[0], UbjsonValue::Null);
+    assert_eq!(values[3], UbjsonValue::Float32(f32::NEG_INFINITY));
+    assert_eq!(values[4], UbjsonValue::Int8(2));
+    assert_eq!(values[5], UbjsonValue::Float64(2.5));
+    assert_eq!(values[7], UbjsonValue::Float64(f64::INFINITY));
+    assert!(matches!(values[8], UbjsonValue::Float64(f) if f.is_nan()));
+    assert_eq!(values[12], UbjsonValue::Object(HashMap::new()));
+
+    let nan = UbjsonValue::Float64(f64::NAN);
+    assert!(nan.semantic_eq(&UbjsonValue::Float32(f32::NAN)));
+    assert_eq!(
+        UbjsonValue::Int64(9_007_199_254_740_993).semantic_cmp(&UbjsonValue::Float64(9_007_199_254_740_992.0)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        UbjsonValue::Array(vec![UbjsonValue::Int8(1)]).semantic_cmp(&UbjsonValue::Array(vec![UbjsonValue::Int8(1), UbjsonValue::Null])),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_semantic_value_in_collections() {
+    let values = vec![
+        UbjsonValue::Int8(1),
+        UbjsonValue::Float64(1.0),
+        UbjsonValue::Char('x'),
+        UbjsonValue::String("x".to_string()),
+        UbjsonValue::Float64(f64::NAN),
+        UbjsonValue::Float32(f32::NAN),
+        UbjsonValue::Array(vec![UbjsonValue::Int16(5)]),
+        UbjsonValue::strongly_typed_array(UbjsonType::UInt8, vec![UbjsonValue::UInt8(5)]),
+    ];
+    let set: HashSet<SemanticValue> = values.iter().cloned().map(SemanticValue).collect();
+    assert_eq!(set.len(), 4);
+    let sorted: BTreeSet<SemanticValue> = values.into_iter().map(SemanticValue::from).collect();
+    assert_eq!(sorted.len(), 4);
+    assert!(sorted.first().unwrap().clone().into_inner().semantic_eq(&UbjsonValue::Int8(1)));
+}
+
+/// Build arrays nested `depth` levels deep around `leaf`.
+fn deep(depth: usize, leaf: UbjsonValue) -> UbjsonValue {
+    let mut value = leaf;
+    for _ in 0..depth {
+        value = UbjsonValue::Array(vec![value]);
+    }
+    value
+}
+
+/// Take nested arrays apart without recursion, so dropping them can't overflow.
+fn dismantle(mut value: UbjsonValue) {
+    while let Some(inner) = value.as_array_mut().and_then(Vec::pop) {
+        value = inner;
+    }
+}
+
+#[test]
+fn test_deep_values_do_not_overflow() {
+    let a = deep(200_000, UbjsonValue::Int8(1));
+    let b = deep(200_000, UbjsonValue::Float64(1.0));
+    let c = deep(200_000, UbjsonValue::Int8(2));
+    assert!(a.semantic_eq(&b));
+    assert_eq!(a.semantic_cmp(&c), Ordering::Less);
+
+    let mut set = HashSet::new();
+    assert!(set.insert(SemanticValue(a)));
+    assert!(set.insert(SemanticValue(c)));
+    let b = SemanticValue(b);
+    assert!(set.contains(&b));
+    for value in set.into_iter().chain([b]) {
+        dismantle(value.into_inner());
+    }
+}
+
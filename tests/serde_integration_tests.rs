@@ -4,7 +4,7 @@
 mod serde_tests {
     use serde::{Deserialize, Serialize};
     use std::collections::HashMap;
-    use ubjson_rs::{UbjsonDeserializer, UbjsonError, UbjsonSerializer};
+    use ubjson_rs::{from_slice, to_vec, SerializerBuilder, UbjsonDeserializer, UbjsonError, UbjsonSerializer};
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     struct Person {
@@ -37,6 +37,7 @@ mod serde_tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant, clippy::bool_assert_comparison)]
     fn test_serialize_deserialize_primitives() {
         // Test boolean
         let mut buffer = Vec::new();
@@ -359,6 +360,7 @@ mod serde_tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_serialize_deserialize_tuple() {
         let original = (42, "hello".to_string(), true, 3.14);
 
@@ -543,4 +545,111 @@ mod serde_tests {
         let result: Result<i32, UbjsonError> = i32::deserialize(deserializer);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_serialize_several_values_by_mut_ref() {
+        let mut buffer = Vec::new();
+        let mut serializer = UbjsonSerializer::new(&mut buffer);
+        42i32.serialize(&mut serializer).unwrap();
+        "second".serialize(&mut serializer).unwrap();
+        vec![1u8, 2, 3].serialize(&mut serializer).unwrap();
+
+        let mut deserializer = UbjsonDeserializer::from_slice(&buffer);
+        assert_eq!(i32::deserialize(&mut deserializer).unwrap(), 42);
+        assert_eq!(String::deserialize(&mut deserializer).unwrap(), "second");
+        assert_eq!(Vec::<u8>::deserialize(&mut deserializer).unwrap(), vec![1, 2, 3]);
+        deserializer.end().unwrap();
+    }
+
+    #[test]
+    fn test_deserialize_by_mut_ref_keeps_position() {
+        let person = Person {
+            name: "Alice".to_string(),
+            age: 30,
+            active: true,
+            height: 1.65,
+        };
+        let mut buffer = Vec::new();
+        let mut serializer = UbjsonSerializer::new(&mut buffer);
+        person.serialize(&mut serializer).unwrap();
+        Status::Pending("review".to_string()).serialize(&mut serializer).unwrap();
+
+        let mut deserializer = UbjsonDeserializer::new(buffer.as_slice());
+        assert_eq!(Person::deserialize(&mut deserializer).unwrap(), person);
+        let after_person = deserializer.position();
+        assert!(after_person > 0 && after_person < buffer.len());
+        assert_eq!(
+            Status::deserialize(&mut deserializer).unwrap(),
+            Status::Pending("review".to_string())
+        );
+        assert_eq!(deserializer.position(), buffer.len());
+        deserializer.end().unwrap();
+    }
+
+    #[test]
+    fn test_end_rejects_trailing_bytes() {
+        let mut buffer = Vec::new();
+        let mut serializer = UbjsonSerializer::new(&mut buffer);
+        true.serialize(&mut serializer).unwrap();
+        false.serialize(&mut serializer).unwrap();
+
+        let mut deserializer = UbjsonDeserializer::from_slice(&buffer);
+        assert!(bool::deserialize(&mut deserializer).unwrap());
+        assert!(matches!(deserializer.end(), Err(UbjsonError::TrailingData { offset: 1 })));
+
+        // Trailing no-op padding is not data
+        let padded = [b'T', b'N', b'N'];
+        let mut deserializer = UbjsonDeserializer::from_slice(&padded);
+        assert!(bool::deserialize(&mut deserializer).unwrap());
+        deserializer.end().unwrap();
+    }
+
+    #[test]
+    fn test_mut_ref_serializer_respects_settings() {
+        let mut buffer = Vec::new();
+        let mut serializer = UbjsonSerializer::with_settings(&mut buffer, true, 1);
+        vec![1i32, 2, 3].serialize(&mut serializer).unwrap();
+        let nested = vec![vec![1i32]];
+        assert!(matches!(
+            nested.serialize(&mut serializer),
+            Err(UbjsonError::DepthLimitExceeded(1))
+        ));
+
+        // The optimized array header was used for the first value
+        assert_eq!(&buffer[..3], b"[$l");
+    }
+
+    #[test]
+    fn test_non_ascii_char_is_written_as_string() {
+        let bytes = to_vec(&'a').unwrap();
+        assert_eq!(bytes, b"Ca");
+
+        let bytes = to_vec(&'€').unwrap();
+        assert_eq!(bytes, "SU\x03€".as_bytes());
+        assert_eq!(from_slice::<char>(&bytes).unwrap(), '€');
+
+        // Older output used a multi-byte char, which is still accepted
+        assert_eq!(from_slice::<char>("C€".as_bytes()).unwrap(), '€');
+        assert!(from_slice::<char>(b"SU\x02ab").is_err());
+    }
+
+    #[test]
+    fn test_optimized_chars_fall_back_to_standard_array() {
+        let chars = vec!['a', 'é', 'z'];
+        let bytes = SerializerBuilder::new()
+            .with_container_optimization(true)
+            .to_vec(&chars)
+            .unwrap();
+        assert_eq!(bytes[0], b'[');
+        assert_ne!(bytes[1], b'$');
+        assert_eq!(from_slice::<Vec<char>>(&bytes).unwrap(), chars);
+
+        let ascii = vec!['a', 'b'];
+        let bytes = SerializerBuilder::new()
+            .with_container_optimization(true)
+            .to_vec(&ascii)
+            .unwrap();
+        assert_eq!(&bytes[..3], b"[$C");
+        assert_eq!(from_slice::<Vec<char>>(&bytes).unwrap(), ascii);
+    }
 }
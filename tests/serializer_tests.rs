@@ -2,6 +2,7 @@ use ubjson_rs::{UbjsonSerializer, UbjsonValue};
 use std::io::Cursor;
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_basic_serialization_integration() {
     let mut buffer = Vec::new();
     let mut serializer = UbjsonSerializer::new(&mut buffer);
@@ -137,6 +138,7 @@ fn test_deeply_nested_containers() {
 }
 
 #[test]
+#[allow(clippy::redundant_closure)]
 fn test_large_containers() {
     let mut buffer = Vec::new();
     let mut serializer = UbjsonSerializer::new(&mut buffer);
@@ -215,6 +217,7 @@ fn test_container_serialization_with_optimization_flag() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_object_key_serialization() {
     let mut buffer = Vec::new();
     let mut serializer = UbjsonSerializer::new(&mut buffer);
@@ -0,0 +1,130 @@
+//! Tests for strict draft-12 conformance mode.
+
+use std::collections::HashMap;
+use ubjson_rs::{value_to_vec, DeserializerBuilder, ErrorKind, SerializerBuilder, UbjsonError, UbjsonType, UbjsonValue};
+
+#[test]
+fn test_strict_accepts_conformant_input() {
+    let value = UbjsonValue::Array(vec![
+        UbjsonValue::Char('a'),
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Int8,
+            count: Some(2),
+            elements: vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2)],
+        },
+    ]);
+    let bytes = value_to_vec(&value).unwrap();
+    let decoded = DeserializerBuilder::new().strict(true).value_from_slice(&bytes).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_strict_rejects_typed_containers_without_count() {
+    let array = b"[$i\x01\x02]";
+    assert!(DeserializerBuilder::new().value_from_slice(array).is_ok());
+    let err = DeserializerBuilder::new().strict(true).value_from_slice(array).unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+    assert_eq!(err.classify(), ErrorKind::Syntax);
+
+    let object = b"{$iU\x01a\x01}";
+    assert!(DeserializerBuilder::new().value_from_slice(object).is_ok());
+    let err = DeserializerBuilder::new()
+        .strict(true)
+        .value_from_reader(&object[..])
+        .unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+}
+
+#[test]
+fn test_strict_rejects_multibyte_char() {
+    let bytes = "Cπ".as_bytes();
+    assert_eq!(
+        DeserializerBuilder::new().value_from_slice(bytes).unwrap(),
+        UbjsonValue::Char('π')
+    );
+    let err = DeserializerBuilder::new().strict(true).value_from_slice(bytes).unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+}
+
+#[test]
+fn test_strict_rejects_invalid_element_types() {
+    for bytes in [&b"[$N#U\x01"[..], b"[$[#U\x01", b"[$]#U\x01", b"{${#U\x01"] {
+        let err = DeserializerBuilder::new().strict(true).value_from_slice(bytes).unwrap_err();
+        assert!(matches!(err, UbjsonError::InvalidFormat(_)), "{:?}: {:?}", bytes, err);
+    }
+}
+
+#[test]
+fn test_strict_serializer_refuses_uncounted_typed_containers() {
+    let array = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Int8,
+        count: None,
+        elements: vec![UbjsonValue::Int8(1)],
+    };
+    assert!(SerializerBuilder::new().value_to_vec(&array).is_ok());
+    let err = SerializerBuilder::new().strict(true).value_to_vec(&array).unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+
+    let mut pairs = HashMap::new();
+    pairs.insert("a".to_string(), UbjsonValue::Int8(1));
+    let object = UbjsonValue::StronglyTypedObject { value_type: UbjsonType::Int8, count: None, pairs };
+    let err = SerializerBuilder::new().strict(true).value_to_vec(&object).unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+}
+
+#[test]
+fn test_serializer_never_writes_multibyte_char() {
+    let bytes = SerializerBuilder::new()
+        .strict(true)
+        .value_to_vec(&UbjsonValue::Char('π'))
+        .unwrap();
+    assert_eq!(bytes, "SU\x02π".as_bytes());
+
+    let array = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::Char,
+        count: Some(2),
+        elements: vec![UbjsonValue::Char('a'), UbjsonValue::Char('é')],
+    };
+    let bytes = SerializerBuilder::new().value_to_vec(&array).unwrap();
+    assert_eq!(bytes, "[CaSU\x02é]".as_bytes());
+    let err = SerializerBuilder::new().strict(true).value_to_vec(&array).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidChar(_)));
+
+    let object = UbjsonValue::StronglyTypedObject {
+        value_type: UbjsonType::Char,
+        count: Some(1),
+        pairs: HashMap::from([("k".to_string(), UbjsonValue::Char('é'))]),
+    };
+    let bytes = SerializerBuilder::new().value_to_vec(&object).unwrap();
+    assert_eq!(bytes, "{U\x01kSU\x02é}".as_bytes());
+    let err = SerializerBuilder::new().strict(true).value_to_vec(&object).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidChar(_)));
+
+    assert_eq!(UbjsonValue::from_char('a'), UbjsonValue::Char('a'));
+    assert_eq!(UbjsonValue::from_char('π'), UbjsonValue::String("π".to_string()));
+}
+
+#[test]
+fn test_serializer_refuses_non_primitive_element_types() {
+    let array = UbjsonValue::StronglyTypedArray {
+        element_type: UbjsonType::ArrayStart,
+        count: Some(0),
+        elements: vec![],
+    };
+    let mut buffer = Vec::new();
+    let err = SerializerBuilder::new().value_to_writer(&mut buffer, &array).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidFormat(_)));
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_strict_output_round_trips_strictly() {
+    let value = UbjsonValue::Array(vec![UbjsonValue::Int16(300); 4]);
+    let bytes = SerializerBuilder::new()
+        .with_container_optimization(true)
+        .strict(true)
+        .value_to_vec(&value)
+        .unwrap();
+    let decoded = DeserializerBuilder::new().strict(true).value_from_slice(&bytes).unwrap();
+    assert_eq!(decoded.len(), Some(4));
+}
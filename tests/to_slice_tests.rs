@@ -0,0 +1,29 @@
+//! Tests for serializing serde values into caller-provided buffers.
+
+#![cfg(feature = "serde")]
+
+use serde::Serialize;
+use ubjson_rs::{to_slice, to_vec, value_from_slice, SerializerBuilder, UbjsonError};
+
+#[test]
+fn test_to_slice_with_options() {
+    #[derive(Serialize)]
+    struct Frame {
+        id: u32,
+        samples: Vec<i16>,
+    }
+
+    let frame = Frame { id: 3, samples: vec![-1; 32] };
+    let mut buffer = [0u8; 256];
+    // Struct fields are written in map order, so compare decoded values
+    let written = to_slice(&mut buffer, &frame).unwrap();
+    assert_eq!(value_from_slice(&buffer[..written]).unwrap(), value_from_slice(&to_vec(&frame).unwrap()).unwrap());
+
+    let builder = SerializerBuilder::new().with_container_optimization(true);
+    let expected = builder.clone().to_vec(&frame).unwrap();
+    let written = builder.clone().to_slice(&mut buffer, &frame).unwrap();
+    assert_eq!(value_from_slice(&buffer[..written]).unwrap(), value_from_slice(&expected).unwrap());
+
+    let err = builder.to_slice(&mut buffer[..expected.len() - 1], &frame).unwrap_err();
+    assert!(matches!(err, UbjsonError::BufferTooSmall { needed } if needed == expected.len()));
+}
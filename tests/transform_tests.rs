@@ -0,0 +1,162 @@
+//! Tests for normalizing, mapping, filtering and walking values.
+
+use std::collections::HashMap;
+use ubjson_rs::{format_path, NormalizeOptions, PathSegment, UbjsonType, UbjsonValue};
+
+fn sample() -> UbjsonValue {
+    let mut scores = HashMap::new();
+    scores.insert("a".to_string(), UbjsonValue::Int16(300));
+    let mut fields = HashMap::new();
+    fields.insert("ids".to_string(), UbjsonValue::strongly_typed_array(
+        UbjsonType::UInt8,
+        vec![UbjsonValue::UInt8(1), UbjsonValue::UInt8(2)],
+    ));
+    fields.insert("scores".to_string(), UbjsonValue::strongly_typed_object(UbjsonType::Int16, scores));
+    fields.insert("big".to_string(), UbjsonValue::HighPrecision("2.5".to_string()));
+    fields.insert("odd".to_string(), UbjsonValue::HighPrecision("1e999".to_string()));
+    fields.insert("name".to_string(), UbjsonValue::String("x".to_string()));
+    UbjsonValue::Object(fields)
+}
+
+fn field<'a>(value: &'a UbjsonValue, key: &str) -> &'a UbjsonValue {
+    &value.as_object().unwrap()[key]
+}
+
+/// Build arrays nested `depth` levels deep, and take them apart again without recursion.
+fn deep(depth: usize) -> UbjsonValue {
+    let mut value = UbjsonValue::Int8(1);
+    for _ in 0..depth {
+        value = UbjsonValue::Array(vec![value]);
+    }
+    value
+}
+
+fn dismantle(mut value: UbjsonValue) {
+    while let Some(inner) = value.as_array_mut().and_then(Vec::pop) {
+        value = inner;
+    }
+}
+
+#[test]
+fn test_normalize_default_changes_nothing() {
+    let mut value = sample();
+    value.normalize(NormalizeOptions::new());
+    assert_eq!(value, sample());
+}
+
+#[test]
+fn test_normalize_flatten_and_widen() {
+    let mut value = sample();
+    value.normalize(
+        NormalizeOptions::new()
+            .with_flatten_typed_containers(true)
+            .with_widen_integers(true)
+            .with_high_precision_to_float(true),
+    );
+    assert_eq!(field(&value, "ids"), &UbjsonValue::Array(vec![UbjsonValue::Int64(1), UbjsonValue::Int64(2)]));
+    assert!(matches!(field(&value, "scores"), UbjsonValue::Object(pairs) if pairs["a"] == UbjsonValue::Int64(300)));
+    assert_eq!(field(&value, "big"), &UbjsonValue::Float64(2.5));
+    assert_eq!(field(&value, "odd"), &UbjsonValue::HighPrecision("1e999".to_string()));
+    assert!(value.semantic_eq(&sample()));
+}
+
+#[test]
+fn test_normalize_retypes_typed_containers() {
+    let mut value = sample();
+    value.normalize(NormalizeOptions::new().with_widen_integers(true));
+    assert_eq!(
+        field(&value, "ids"),
+        &UbjsonValue::strongly_typed_array(UbjsonType::Int64, vec![UbjsonValue::Int64(1), UbjsonValue::Int64(2)])
+    );
+    let bytes = ubjson_rs::value_to_vec(&value).unwrap();
+    assert_eq!(ubjson_rs::value_from_slice(&bytes).unwrap(), value);
+
+    let mut numbers = UbjsonValue::strongly_typed_array(
+        UbjsonType::HighPrecision,
+        vec![UbjsonValue::HighPrecision("1.5".to_string()), UbjsonValue::HighPrecision("?".to_string())],
+    );
+    numbers.normalize(NormalizeOptions::new().with_high_precision_to_float(true));
+    assert_eq!(numbers, UbjsonValue::Array(vec![
+        UbjsonValue::Float64(1.5),
+        UbjsonValue::HighPrecision("?".to_string()),
+    ]));
+}
+
+#[test]
+fn test_map_values() {
+    let mut value = sample();
+    value.map_values(|v| match v {
+        UbjsonValue::String(s) => UbjsonValue::String(s.to_uppercase()),
+        UbjsonValue::UInt8(n) => UbjsonValue::UInt8(n * 10),
+        other => other,
+    });
+    assert_eq!(field(&value, "name"), &UbjsonValue::String("X".to_string()));
+    assert_eq!(field(&value, "ids").as_array().unwrap()[1], UbjsonValue::UInt8(20));
+
+    // Children of a replacement container are visited too
+    let mut value = UbjsonValue::Null;
+    value.map_values(|v| match v {
+        UbjsonValue::Null => UbjsonValue::Array(vec![UbjsonValue::Bool(false)]),
+        UbjsonValue::Bool(b) => UbjsonValue::Bool(!b),
+        other => other,
+    });
+    assert_eq!(value, UbjsonValue::Array(vec![UbjsonValue::Bool(true)]));
+}
+
+#[test]
+fn test_retain() {
+    let mut value = UbjsonValue::Array(vec![
+        UbjsonValue::Int8(1),
+        UbjsonValue::Null,
+        UbjsonValue::strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(-1), UbjsonValue::Int8(2)]),
+    ]);
+    let mut seen = Vec::new();
+    value.retain(|path, v| {
+        seen.push(format_path(path));
+        !v.is_null() && v.as_i64().is_none_or(|n| n > 0)
+    });
+    assert_eq!(value, UbjsonValue::Array(vec![
+        UbjsonValue::Int8(1),
+        UbjsonValue::strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(2)]),
+    ]));
+    assert_eq!(seen, ["$[0]", "$[1]", "$[2]", "$[2][0]", "$[2][1]"]);
+
+    let mut value = sample();
+    value.retain(|path, _| path != [PathSegment::Key("scores".to_string())]);
+    assert_eq!(value.len(), Some(4));
+}
+
+#[test]
+fn test_walk_paths() {
+    let mut inner = HashMap::new();
+    inner.insert("b c".to_string(), UbjsonValue::Int8(1));
+    inner.insert("a".to_string(), UbjsonValue::Array(vec![UbjsonValue::Null]));
+    let value = UbjsonValue::Object(inner);
+
+    let mut visited = Vec::new();
+    value.walk(|path, v| visited.push((format_path(path), v.type_name())));
+    assert_eq!(visited, [
+        ("$".to_string(), "object"),
+        ("$.a".to_string(), "array"),
+        ("$.a[0]".to_string(), "null"),
+        ("$[\"b c\"]".to_string(), "int8"),
+    ]);
+}
+
+#[test]
+fn test_deep_documents_do_not_overflow() {
+    let mut value = deep(200_000);
+
+    let mut max_depth = 0;
+    value.walk(|path, _| max_depth = max_depth.max(path.len()));
+    assert_eq!(max_depth, 200_000);
+
+    value.normalize(NormalizeOptions::new().with_widen_integers(true));
+    value.map_values(|v| v);
+    value.retain(|_, _| true);
+
+    let mut leaf = None;
+    value.walk(|_, v| if !v.is_array() { leaf = Some(v.clone()) });
+    assert_eq!(leaf, Some(UbjsonValue::Int64(1)));
+    dismantle(value);
+}
@@ -0,0 +1,96 @@
+//! Tests for strongly-typed container validation.
+
+use std::collections::HashMap;
+use ubjson_rs::{
+    value_to_writer, ObjectBuilder, PathSegment, SerializerBuilder, UbjsonError, UbjsonType, UbjsonValue,
+    Violation, ViolationKind,
+};
+
+fn key(k: &str) -> PathSegment {
+    PathSegment::Key(k.to_string())
+}
+
+#[test]
+fn test_checked_constructors() {
+    let array = UbjsonValue::try_strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(1)]).unwrap();
+    assert_eq!(array, UbjsonValue::strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(1)]));
+
+    let err = UbjsonValue::try_strongly_typed_array(UbjsonType::Float32, vec![UbjsonValue::Int8(1)]).unwrap_err();
+    assert!(matches!(err, UbjsonError::TypedContainerMismatch { expected: UbjsonType::Float32, found: UbjsonType::Int8 }));
+    let err = UbjsonValue::try_strongly_typed_array(UbjsonType::ArrayStart, Vec::new()).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidFormat(_)));
+    // Non-ASCII chars are only rejected when strict serialization writes them
+    let chars = UbjsonValue::try_strongly_typed_array(UbjsonType::Char, vec![UbjsonValue::Char('é')]).unwrap();
+    assert!(chars.validate().is_ok());
+    let err = SerializerBuilder::new().strict(true).value_to_vec(&chars).unwrap_err();
+    assert!(matches!(err, UbjsonError::InvalidChar(_)));
+
+    let mut pairs = HashMap::new();
+    pairs.insert("a".to_string(), UbjsonValue::String("x".to_string()));
+    assert!(UbjsonValue::try_strongly_typed_object(UbjsonType::String, pairs.clone()).is_ok());
+    pairs.insert("b".to_string(), UbjsonValue::Null);
+    let err = UbjsonValue::try_strongly_typed_object(UbjsonType::String, pairs).unwrap_err();
+    assert!(matches!(err, UbjsonError::TypedContainerMismatch { expected: UbjsonType::String, found: UbjsonType::Null }));
+}
+
+#[test]
+fn test_validate_reports_every_violation_with_its_path() {
+    let value = ObjectBuilder::new()
+        .insert("ok", UbjsonValue::strongly_typed_array(UbjsonType::UInt8, vec![UbjsonValue::UInt8(1)]))
+        .insert("list", vec![UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Float32,
+            count: Some(5),
+            elements: vec![UbjsonValue::Float32(1.0), UbjsonValue::Int8(2), UbjsonValue::Float32(3.0)],
+        }])
+        .insert("chars", UbjsonValue::strongly_typed_object(UbjsonType::Char, HashMap::from([
+            ("a".to_string(), UbjsonValue::Char('a')),
+            ("b".to_string(), UbjsonValue::Char('π')),
+        ])))
+        .insert("bad", UbjsonValue::StronglyTypedObject {
+            value_type: UbjsonType::ObjectStart,
+            count: None,
+            pairs: HashMap::new(),
+        })
+        .build();
+
+    let violations = value.validate().unwrap_err();
+    assert_eq!(violations, vec![
+        Violation { path: vec![key("bad")], kind: ViolationKind::NonPrimitiveType(UbjsonType::ObjectStart) },
+        Violation {
+            path: vec![key("list"), PathSegment::Index(0)],
+            kind: ViolationKind::CountMismatch { count: 5, actual: 3 },
+        },
+        Violation {
+            path: vec![key("list"), PathSegment::Index(0), PathSegment::Index(1)],
+            kind: ViolationKind::TypeMismatch { expected: UbjsonType::Float32, found: UbjsonType::Int8 },
+        },
+    ]);
+    assert_eq!(violations[2].to_string(), "$.list[0][1]: value type int8 does not match element type float32");
+
+    assert!(UbjsonValue::Array(vec![UbjsonValue::Int8(1)]).validate().is_ok());
+}
+
+#[test]
+fn test_serializer_checks_before_writing() {
+    let value = UbjsonValue::Array(vec![
+        UbjsonValue::String("written first".to_string()),
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Int8,
+            count: Some(3),
+            elements: vec![UbjsonValue::Int8(1)],
+        },
+    ]);
+    let mut buffer = Vec::new();
+    let err = value_to_writer(&mut buffer, &value).unwrap_err();
+    assert!(matches!(err, UbjsonError::LengthMismatch { expected: 3, actual: 1 }));
+    assert!(buffer.is_empty());
+
+    let value = UbjsonValue::Array(vec![
+        UbjsonValue::Int8(1),
+        UbjsonValue::StronglyTypedArray { element_type: UbjsonType::Int8, count: None, elements: Vec::new() },
+    ]);
+    let mut buffer = Vec::new();
+    let err = SerializerBuilder::new().strict(true).value_to_writer(&mut buffer, &value).unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+    assert!(buffer.is_empty());
+}
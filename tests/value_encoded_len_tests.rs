@@ -0,0 +1,40 @@
+//! Tests for computing encoded sizes of values without writing.
+
+use std::collections::HashMap;
+use ubjson_rs::{encoded_len, value_to_vec, SerializerBuilder, UbjsonError, UbjsonType, UbjsonValue};
+
+fn sample() -> UbjsonValue {
+    let mut object = HashMap::new();
+    // Lengths on both sides of each length-prefix width
+    for len in [0, 127, 128, 255, 256, 32767, 32768] {
+        object.insert(format!("s{}", len), UbjsonValue::String("x".repeat(len)));
+    }
+    object.insert("ints".to_string(), (0..300).map(UbjsonValue::Int32).collect());
+    object.insert("mixed".to_string(), vec![UbjsonValue::Int8(1), UbjsonValue::Null].into());
+    object.insert(
+        "typed".to_string(),
+        UbjsonValue::strongly_typed_array(UbjsonType::Float64, vec![UbjsonValue::Float64(1.5); 3]),
+    );
+    object.insert("hp".to_string(), UbjsonValue::HighPrecision("3.14159".to_string()));
+    UbjsonValue::Object(object)
+}
+
+#[test]
+fn test_encoded_len_matches_output() {
+    let value = sample();
+    assert_eq!(encoded_len(&value).unwrap(), value_to_vec(&value).unwrap().len());
+    assert_eq!(encoded_len(&UbjsonValue::Null).unwrap(), 1);
+
+    for optimize in [false, true] {
+        let builder = SerializerBuilder::new().with_container_optimization(optimize);
+        let bytes = builder.clone().value_to_vec(&value).unwrap();
+        assert_eq!(builder.value_encoded_len(&value).unwrap(), bytes.len());
+    }
+}
+
+#[test]
+fn test_encoded_len_reports_serializer_errors() {
+    let value = UbjsonValue::StronglyTypedArray { element_type: UbjsonType::Int8, count: None, elements: Vec::new() };
+    let err = SerializerBuilder::new().strict(true).value_encoded_len(&value).unwrap_err();
+    assert!(matches!(err, UbjsonError::NonConformant(_)));
+}
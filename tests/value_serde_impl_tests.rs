@@ -0,0 +1,107 @@
+//! Tests for the Serialize and Deserialize impls on UbjsonValue.
+
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ubjson_rs::{from_slice, from_value, to_value, to_vec, value_from_slice, value_to_vec, UbjsonType, UbjsonValue};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Event {
+    id: u32,
+    payload: UbjsonValue,
+    extra: Option<UbjsonValue>,
+}
+
+fn detailed() -> UbjsonValue {
+    let mut scores = HashMap::new();
+    scores.insert("a".to_string(), UbjsonValue::Int16(300));
+    scores.insert("b".to_string(), UbjsonValue::Int16(-2));
+
+    let mut fields = HashMap::new();
+    fields.insert("big".to_string(), UbjsonValue::HighPrecision("1.5e400".to_string()));
+    fields.insert("letter".to_string(), UbjsonValue::Char('x'));
+    fields.insert("small".to_string(), UbjsonValue::UInt8(7));
+    fields.insert(
+        "counted".to_string(),
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Int8,
+            count: Some(2),
+            elements: vec![UbjsonValue::Int8(1), UbjsonValue::Int8(-1)],
+        },
+    );
+    fields.insert(
+        "uncounted".to_string(),
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::Float32,
+            count: None,
+            elements: vec![UbjsonValue::Float32(0.5)],
+        },
+    );
+    fields.insert(
+        "scores".to_string(),
+        UbjsonValue::StronglyTypedObject { value_type: UbjsonType::Int16, count: Some(2), pairs: scores },
+    );
+    fields.insert("single".to_string(), UbjsonValue::Object(
+        [("only".to_string(), UbjsonValue::Null)].into_iter().collect(),
+    ));
+    UbjsonValue::Object(fields)
+}
+
+#[test]
+fn test_embedded_value_round_trips_through_bytes() {
+    let event = Event { id: 9, payload: detailed(), extra: Some(UbjsonValue::Int64(-5)) };
+    let bytes = to_vec(&event).unwrap();
+    assert_eq!(from_slice::<Event>(&bytes).unwrap(), event);
+
+    let none = Event { id: 1, payload: UbjsonValue::Null, extra: None };
+    assert_eq!(from_slice::<Event>(&to_vec(&none).unwrap()).unwrap(), none);
+}
+
+#[test]
+fn test_value_serializes_like_value_to_vec() {
+    let value = detailed();
+    let bytes = to_vec(&value).unwrap();
+    assert_eq!(value_from_slice(&bytes).unwrap(), value);
+    assert_eq!(from_slice::<UbjsonValue>(&value_to_vec(&value).unwrap()).unwrap(), value);
+}
+
+#[test]
+fn test_value_round_trips_through_value_tree() {
+    let value = UbjsonValue::Array(vec![detailed(), UbjsonValue::Char('é')]);
+    assert_eq!(to_value(&value).unwrap(), value);
+    assert_eq!(from_value::<UbjsonValue>(value.clone()).unwrap(), value);
+}
+
+#[test]
+fn test_value_to_and_from_json() {
+    let value = UbjsonValue::Array(vec![
+        UbjsonValue::HighPrecision("12345678901234567890123".to_string()),
+        UbjsonValue::Char('é'),
+        UbjsonValue::StronglyTypedArray {
+            element_type: UbjsonType::UInt8,
+            count: Some(2),
+            elements: vec![UbjsonValue::UInt8(1), UbjsonValue::UInt8(2)],
+        },
+        UbjsonValue::Bool(true),
+        UbjsonValue::Null,
+    ]);
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, r#"["12345678901234567890123","é",[1,2],true,null]"#);
+
+    let parsed: UbjsonValue = serde_json::from_str(r#"{"n": -3, "big": 18446744073709551615, "f": 1.5, "s": "hi"}"#).unwrap();
+    let mut expected = HashMap::new();
+    expected.insert("n".to_string(), UbjsonValue::Int64(-3));
+    expected.insert("big".to_string(), UbjsonValue::HighPrecision("18446744073709551615".to_string()));
+    expected.insert("f".to_string(), UbjsonValue::Float64(1.5));
+    expected.insert("s".to_string(), UbjsonValue::String("hi".to_string()));
+    assert_eq!(parsed, UbjsonValue::Object(expected));
+}
+
+#[test]
+fn test_reserved_looking_keys_stay_plain_objects() {
+    let parsed: UbjsonValue = serde_json::from_str(r#"{"$ubjson::HighPrecision": 5}"#).unwrap();
+    let mut expected = HashMap::new();
+    expected.insert("$ubjson::HighPrecision".to_string(), UbjsonValue::Int64(5));
+    assert_eq!(parsed, UbjsonValue::Object(expected));
+}
@@ -0,0 +1,118 @@
+//! Tests for converting between serde types and UbjsonValue trees.
+
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ubjson_rs::{
+    from_slice, from_value, from_value_ref, to_value, to_vec, value_from_slice, value_to_vec, UbjsonError,
+    UbjsonValue,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Point,
+    Circle(f64),
+    Rect { width: u32, height: u32 },
+    Line(i16, i16),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Drawing {
+    name: String,
+    shapes: Vec<Shape>,
+    tags: HashMap<String, u64>,
+    layer: Option<u8>,
+    initial: char,
+    data: Vec<u8>,
+}
+
+fn sample() -> Drawing {
+    let mut tags = HashMap::new();
+    tags.insert("big".to_string(), u64::MAX);
+    tags.insert("small".to_string(), 3);
+    Drawing {
+        name: "sketch".to_string(),
+        shapes: vec![
+            Shape::Point,
+            Shape::Circle(1.5),
+            Shape::Rect { width: 3, height: 4 },
+            Shape::Line(-1, 1),
+        ],
+        tags,
+        layer: None,
+        initial: 'é',
+        data: vec![0, 1, 255],
+    }
+}
+
+#[test]
+fn test_to_value_matches_encoded_form() {
+    let drawing = sample();
+    let value = to_value(&drawing).unwrap();
+    let decoded = value_from_slice(&to_vec(&drawing).unwrap()).unwrap();
+    assert_eq!(value, decoded);
+}
+
+#[test]
+fn test_from_value_round_trip() {
+    let drawing = sample();
+    let value = to_value(&drawing).unwrap();
+    assert_eq!(from_value::<Drawing>(value.clone()).unwrap(), drawing);
+    assert_eq!(from_value_ref::<Drawing>(&value).unwrap(), drawing);
+}
+
+#[test]
+fn test_from_value_ref_borrows_strings() {
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        tags: HashMap<&'a str, i32>,
+    }
+
+    let mut tags = HashMap::new();
+    tags.insert("answer".to_string(), UbjsonValue::Int8(42));
+    let mut object = HashMap::new();
+    object.insert("name".to_string(), UbjsonValue::String("borrowed".to_string()));
+    object.insert("tags".to_string(), UbjsonValue::Object(tags));
+    let value = UbjsonValue::Object(object);
+
+    let borrowed: Borrowed = from_value_ref(&value).unwrap();
+    assert_eq!(borrowed.name, "borrowed");
+    assert_eq!(borrowed.tags["answer"], 42);
+}
+
+#[test]
+fn test_edit_value_before_writing() {
+    let mut value = to_value(&sample()).unwrap();
+    if let UbjsonValue::Object(fields) = &mut value {
+        fields.insert("name".to_string(), UbjsonValue::String("edited".to_string()));
+        fields.insert("layer".to_string(), UbjsonValue::UInt8(2));
+    }
+
+    let drawing: Drawing = from_slice(&value_to_vec(&value).unwrap()).unwrap();
+    assert_eq!(drawing.name, "edited");
+    assert_eq!(drawing.layer, Some(2));
+}
+
+#[test]
+fn test_from_value_integer_conversions() {
+    assert_eq!(from_value::<i32>(UbjsonValue::Int64(7)).unwrap(), 7);
+    assert_eq!(from_value::<u64>(UbjsonValue::HighPrecision(u64::MAX.to_string())).unwrap(), u64::MAX);
+    assert_eq!(from_value::<f64>(UbjsonValue::Int16(-3)).unwrap(), -3.0);
+    assert!(matches!(
+        from_value::<u8>(UbjsonValue::Int16(300)),
+        Err(UbjsonError::InvalidType { expected: "u8", found: "int16" })
+    ));
+    assert!(matches!(
+        from_value::<bool>(UbjsonValue::Null),
+        Err(UbjsonError::InvalidType { expected: "bool", found: "null" })
+    ));
+}
+
+#[test]
+fn test_nested_unit_variants_through_bytes() {
+    let shapes = vec![Shape::Point, Shape::Circle(2.0)];
+    let bytes = to_vec(&shapes).unwrap();
+    assert_eq!(from_slice::<Vec<Shape>>(&bytes).unwrap(), shapes);
+}
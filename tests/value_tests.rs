@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use ubjson_rs::{UbjsonType, UbjsonValue};
+use ubjson_rs::{UbjsonError, UbjsonType, UbjsonValue};
 
 #[test]
 fn test_value_types() {
@@ -11,6 +11,7 @@ fn test_value_types() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_type_checks() {
     let null_val = UbjsonValue::Null;
     let bool_val = UbjsonValue::Bool(true);
@@ -30,6 +31,7 @@ fn test_type_checks() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_conversions() {
     assert_eq!(UbjsonValue::from(true), UbjsonValue::Bool(true));
     assert_eq!(UbjsonValue::from(42i32), UbjsonValue::Int32(42));
@@ -50,4 +52,78 @@ fn test_container_length() {
     
     assert!(empty_array.is_empty());
     assert!(!array.is_empty());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_widening_accessors() {
+    assert_eq!(UbjsonValue::Int8(-5).as_i64(), Some(-5));
+    assert_eq!(UbjsonValue::UInt8(200).as_i64(), Some(200));
+    assert_eq!(UbjsonValue::Int32(7).as_u64(), Some(7));
+    assert_eq!(UbjsonValue::Int16(-1).as_u64(), None);
+    assert_eq!(UbjsonValue::HighPrecision(u64::MAX.to_string()).as_u64(), Some(u64::MAX));
+    assert_eq!(UbjsonValue::HighPrecision(u64::MAX.to_string()).as_i64(), None);
+    assert_eq!(UbjsonValue::Float64(1.5).as_i64(), None);
+
+    assert_eq!(UbjsonValue::Float32(0.5).as_f64(), Some(0.5));
+    assert_eq!(UbjsonValue::Int64(-3).as_f64(), Some(-3.0));
+    assert_eq!(UbjsonValue::HighPrecision("2.5e3".to_string()).as_f64(), Some(2500.0));
+    assert_eq!(UbjsonValue::HighPrecision("1e400".to_string()).as_f64(), None);
+    assert_eq!(UbjsonValue::String("1".to_string()).as_f64(), None);
+
+    assert_eq!(UbjsonValue::Bool(true).as_bool(), Some(true));
+    assert_eq!(UbjsonValue::Char('x').as_char(), Some('x'));
+    assert_eq!(UbjsonValue::String("hi".to_string()).as_str(), Some("hi"));
+    assert_eq!(UbjsonValue::Char('x').as_str(), None);
+}
+
+#[test]
+fn test_container_accessors_treat_typed_variants_alike() {
+    let plain = UbjsonValue::Array(vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2)]);
+    let mut typed = UbjsonValue::strongly_typed_array(UbjsonType::Int8, vec![UbjsonValue::Int8(1), UbjsonValue::Int8(2)]);
+    assert_eq!(plain.as_array(), typed.as_array());
+    assert_eq!(typed.elements().filter_map(UbjsonValue::as_i64).sum::<i64>(), 3);
+    for element in typed.elements_mut() {
+        *element = UbjsonValue::Int8(9);
+    }
+    assert_eq!(typed.as_array().unwrap(), &vec![UbjsonValue::Int8(9); 2]);
+
+    let mut pairs = HashMap::new();
+    pairs.insert("a".to_string(), UbjsonValue::Int16(300));
+    let plain = UbjsonValue::Object(pairs.clone());
+    let typed = UbjsonValue::strongly_typed_object(UbjsonType::Int16, pairs);
+    assert_eq!(plain.as_object(), typed.as_object());
+    let entries: Vec<_> = typed.entries().collect();
+    assert_eq!(entries, vec![(&"a".to_string(), &UbjsonValue::Int16(300))]);
+
+    assert!(UbjsonValue::Null.as_array().is_none());
+    assert_eq!(UbjsonValue::Null.elements().count(), 0);
+    assert_eq!(plain.elements().count(), 0);
+    assert_eq!(UbjsonValue::Int8(1).entries().count(), 0);
+}
+
+#[test]
+fn test_try_from_primitives() {
+    assert_eq!(u8::try_from(UbjsonValue::Int64(255)).unwrap(), 255);
+    assert_eq!(i16::try_from(UbjsonValue::UInt8(200)).unwrap(), 200);
+    assert_eq!(u128::try_from(UbjsonValue::HighPrecision(u128::MAX.to_string())).unwrap(), u128::MAX);
+    assert!(matches!(
+        u8::try_from(UbjsonValue::Int16(-1)),
+        Err(UbjsonError::InvalidType { expected: "u8", found: "int16" })
+    ));
+    assert!(matches!(
+        i32::try_from(UbjsonValue::Float64(1.0)),
+        Err(UbjsonError::InvalidType { expected: "i32", found: "float64" })
+    ));
+
+    assert!(bool::try_from(UbjsonValue::Bool(false)).is_ok_and(|b| !b));
+    assert_eq!(f64::try_from(UbjsonValue::Int32(4)).unwrap(), 4.0);
+    assert_eq!(f32::try_from(UbjsonValue::Float64(0.25)).unwrap(), 0.25);
+    assert_eq!(char::try_from(UbjsonValue::Char('a')).unwrap(), 'a');
+    assert_eq!(char::try_from(UbjsonValue::String("é".to_string())).unwrap(), 'é');
+    assert!(char::try_from(UbjsonValue::String("ab".to_string())).is_err());
+    assert_eq!(String::try_from(UbjsonValue::Char('z')).unwrap(), "z");
+    assert!(matches!(
+        String::try_from(UbjsonValue::Null),
+        Err(UbjsonError::InvalidType { expected: "string", found: "null" })
+    ));
+}
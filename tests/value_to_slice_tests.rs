@@ -0,0 +1,45 @@
+//! Tests for serializing values into caller-provided buffers.
+
+use ubjson_rs::{
+    encoded_len, value_from_slice, value_to_slice, value_to_vec, ErrorKind, SerializerBuilder, UbjsonError,
+    UbjsonValue,
+};
+
+fn sample() -> UbjsonValue {
+    UbjsonValue::Array(vec![
+        UbjsonValue::String("x".repeat(200)),
+        UbjsonValue::Int32(42),
+        UbjsonValue::Array(vec![UbjsonValue::Float64(0.5); 10]),
+    ])
+}
+
+#[test]
+fn test_value_to_slice() {
+    let value = sample();
+    let expected = value_to_vec(&value).unwrap();
+
+    let mut buffer = [0u8; 512];
+    let written = value_to_slice(&mut buffer, &value).unwrap();
+    assert_eq!(&buffer[..written], &expected[..]);
+    assert_eq!(value_from_slice(&buffer[..written]).unwrap(), value);
+
+    // An exact fit succeeds, one byte less fails
+    let mut buffer = vec![0u8; expected.len()];
+    assert_eq!(value_to_slice(&mut buffer, &value).unwrap(), expected.len());
+    let mut buffer = vec![0u8; expected.len() - 1];
+    let err = value_to_slice(&mut buffer, &value).unwrap_err();
+    assert!(matches!(err, UbjsonError::BufferTooSmall { needed } if needed == expected.len()));
+    assert_eq!(err.classify(), ErrorKind::Limit);
+
+    let err = value_to_slice(&mut [], &UbjsonValue::Null).unwrap_err();
+    assert!(matches!(err, UbjsonError::BufferTooSmall { needed: 1 }));
+}
+
+#[test]
+fn test_to_slice_reports_other_errors() {
+    let value = UbjsonValue::Array(vec![UbjsonValue::Array(vec![UbjsonValue::Null])]);
+    let mut buffer = [0u8; 2];
+    let err = SerializerBuilder::new().with_max_depth(1).value_to_slice(&mut buffer, &value).unwrap_err();
+    assert!(matches!(err, UbjsonError::DepthLimitExceeded(1)));
+    assert_eq!(encoded_len(&value).unwrap(), 5);
+}